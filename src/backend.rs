@@ -0,0 +1,396 @@
+//! Sources of dictionary entries.
+//!
+//! Commands only talk to [`DictionaryBackend`], so the source can be switched
+//! per deployment with the `DICTIONARY_BACKEND` secret.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use shuttle_runtime::SecretStore;
+
+use crate::entry::HanjaEntry;
+use crate::grade::Grades;
+use crate::store::Store;
+use crate::text::{sound, spoken};
+use crate::unihan::UnihanIndex;
+use crate::Error;
+
+pub mod cache;
+pub mod coalesce;
+pub mod daum;
+pub mod fallback;
+pub mod grade;
+#[cfg(test)]
+pub mod stub;
+pub mod unihan;
+
+/// Candidate entry found by [`DictionaryBackend::search`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// Backend-specific identifier of the entry.
+    pub id: String,
+    pub headword: String,
+    pub reading: Option<String>,
+    /// Short gloss shown when choosing between candidates.
+    pub gloss: Option<String>,
+    /// [`DictionaryBackend::name`] of the backend that found the entry.
+    pub source: String,
+}
+
+/// Character listed by [`DictionaryBackend::by_radical`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadicalHit {
+    pub hit: SearchHit,
+    /// Strokes of the character not counted in the radical.
+    pub remaining_strokes: u8,
+}
+
+/// Why a backend could not produce an entry.
+#[derive(Debug, Clone)]
+pub enum LookupError {
+    NotFound,
+    /// The dictionary answered with an error status.
+    Http(reqwest::StatusCode),
+    Timeout,
+    Network(Arc<reqwest::Error>),
+    /// The page did not contain the element matched by `selector`.
+    Parse {
+        selector: &'static str,
+    },
+}
+
+impl LookupError {
+    /// Message shown to Discord users, in Korean when `locale` asks for it.
+    pub fn user_message(&self, locale: Option<&str>) -> String {
+        let korean = locale.is_some_and(|locale| locale.starts_with("ko"));
+        match (self, korean) {
+            (Self::NotFound, false) => "No result".to_string(),
+            (Self::NotFound, true) => "검색 결과가 없습니다.".to_string(),
+            (Self::Http(status), false) => {
+                format!("The dictionary answered with `{status}`. Please try again later.")
+            }
+            (Self::Http(status), true) => {
+                format!("사전 서버가 `{status}` 오류를 돌려주었습니다. 잠시 후 다시 시도해 주세요.")
+            }
+            (Self::Timeout, false) => "The dictionary did not respond in time.".to_string(),
+            (Self::Timeout, true) => "사전 서버가 제때 응답하지 않았습니다.".to_string(),
+            (Self::Network(_), false) => "Could not reach the dictionary.".to_string(),
+            (Self::Network(_), true) => "사전 서버에 연결하지 못했습니다.".to_string(),
+            (Self::Parse { selector }, false) => {
+                format!("Could not read the dictionary page (`{selector}` is missing).")
+            }
+            (Self::Parse { selector }, true) => {
+                format!("사전 페이지를 읽지 못했습니다 (`{selector}` 없음).")
+            }
+        }
+    }
+}
+
+impl fmt::Display for LookupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "no matching entry"),
+            Self::Http(status) => write!(f, "dictionary answered with {status}"),
+            Self::Timeout => write!(f, "dictionary request timed out"),
+            Self::Network(e) => write!(f, "dictionary request failed: {e}"),
+            Self::Parse { selector } => write!(f, "no element matches `{selector}`"),
+        }
+    }
+}
+
+impl std::error::Error for LookupError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Network(e) => Some(&**e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for LookupError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Self::Timeout
+        } else if let Some(status) = e.status() {
+            Self::Http(status)
+        } else {
+            Self::Network(Arc::new(e))
+        }
+    }
+}
+
+#[async_trait]
+pub trait DictionaryBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// List candidate entries for `query`, best first.
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError>;
+
+    /// Fetch the core of an entry, at least its reading.
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError>;
+
+    /// Add meanings, examples and other supplementary data to `entry`.
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError>;
+
+    /// Fetch and supplement the entry of `hit`.
+    async fn fetch(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        let mut entry = self.entry(hit).await?;
+        self.supplement(hit, &mut entry).await?;
+        Ok(entry)
+    }
+
+    /// List hanja read as hangul `reading`, most common first: characters
+    /// for a single syllable, words of as many characters for more.
+    async fn by_reading(&self, reading: &str) -> Result<Vec<SearchHit>, LookupError> {
+        let reading = reading.trim();
+        let hits = self.search(reading).await?;
+        Ok(hits
+            .into_iter()
+            .filter(|hit| {
+                hit.headword.chars().count() == reading.chars().count()
+                    && hit
+                        .reading
+                        .as_deref()
+                        .is_some_and(|spelled| spoken(&hit.headword, spelled) == reading)
+            })
+            .collect())
+    }
+
+    /// List single characters glossed as `meaning` (훈), optionally read as
+    /// `reading` (음), best match first.
+    async fn by_meaning(
+        &self,
+        meaning: &str,
+        reading: Option<&str>,
+    ) -> Result<Vec<SearchHit>, LookupError> {
+        let query = match reading {
+            Some(reading) => format!("{meaning} {reading}"),
+            None => meaning.to_string(),
+        };
+        let hits = self.search(&query).await?;
+        Ok(rank_by_meaning(meaning, reading, hits))
+    }
+
+    /// List characters under Kangxi radical `number` by remaining strokes,
+    /// like the 부수 색인 of a paper dictionary.
+    ///
+    /// Backends without such an index find nothing.
+    async fn by_radical(&self, _number: u8) -> Result<Vec<RadicalHit>, LookupError> {
+        Ok(Vec::new())
+    }
+
+    /// Fetch the best [`plausible`] candidate for `query`.
+    async fn lookup(&self, query: &str) -> Result<Option<HanjaEntry>, LookupError> {
+        let hits = self.search(query).await?;
+        match plausible(query, hits).first() {
+            Some(hit) => self.fetch(hit).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Candidates whose headword is `query`, or else starts with it.
+pub fn plausible(query: &str, hits: Vec<SearchHit>) -> Vec<SearchHit> {
+    let query = query.trim();
+    let (exact, rest): (Vec<_>, Vec<_>) = hits.into_iter().partition(|hit| hit.headword == query);
+    if exact.is_empty() {
+        rest.into_iter()
+            .filter(|hit| hit.headword.starts_with(query))
+            .collect()
+    } else {
+        exact
+    }
+}
+
+/// Single-character `hits` whose gloss mentions `meaning` and whose sound is
+/// `reading`, if given.
+///
+/// A gloss item spelling out both, like `배울 학`, ranks first, then items
+/// starting with `meaning`, then items merely containing it. Ties keep the
+/// order of `hits`.
+pub fn rank_by_meaning(
+    meaning: &str,
+    reading: Option<&str>,
+    hits: Vec<SearchHit>,
+) -> Vec<SearchHit> {
+    let meaning = meaning.trim().to_lowercase();
+    let mut ranked = hits
+        .into_iter()
+        .filter(|hit| hit.headword.chars().count() == 1)
+        .filter(|hit| {
+            reading.is_none_or(|reading| hit.reading.as_deref().map(sound) == Some(reading.trim()))
+        })
+        .filter_map(|hit| {
+            let gloss = hit.gloss.as_deref()?.to_lowercase();
+            let sound = hit.reading.as_deref().map(sound).unwrap_or_default();
+            let rank = gloss
+                .split([',', ';'])
+                .map(str::trim)
+                .filter_map(|item| {
+                    if item == format!("{meaning} {sound}") {
+                        Some(0)
+                    } else if item == meaning || item.starts_with(&format!("{meaning} ")) {
+                        Some(1)
+                    } else if item.contains(&meaning) {
+                        Some(2)
+                    } else {
+                        None
+                    }
+                })
+                .min()?;
+            Some((rank, hit))
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked.into_iter().map(|(_, hit)| hit).collect()
+}
+
+/// Key identifying equivalent queries: trimmed, with inner whitespace collapsed.
+pub fn normalize(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Key identifying the entry of `hit` across backends.
+pub fn hit_key(hit: &SearchHit) -> String {
+    format!("{}:{}", hit.source, hit.id)
+}
+
+/// Build the backend selected by `DICTIONARY_BACKEND`.
+///
+/// Online backends fall back to the Unihan index, loaded from `UNIHAN_PATH`
+/// or the bundled subset, when they fail. Their lookups are cached according
+/// to `CACHE_TTL_SECS` and `CACHE_CAPACITY` and saved to `store`. Everything
+/// is graded from `grades`.
+pub fn from_secrets(
+    secrets: &SecretStore,
+    client: reqwest::Client,
+    grades: Arc<Grades>,
+    store: Arc<Store>,
+) -> Result<Box<dyn DictionaryBackend>, Error> {
+    let index = match secrets.get("UNIHAN_PATH") {
+        Some(path) => UnihanIndex::load(&path)
+            .map_err(|e| format!("failed to load Unihan data from '{path}': {e}"))?,
+        None => UnihanIndex::bundled(),
+    };
+    let offline = Box::new(unihan::UnihanBackend::new(Arc::new(index)));
+    let base_url = secrets.get("DAUM_BASE_URL");
+    let base_url = base_url.as_deref().unwrap_or(daum::DEFAULT_BASE_URL);
+    let ttl = parse_secret(secrets, "CACHE_TTL_SECS")?.unwrap_or(24 * 60 * 60);
+    let capacity = parse_secret(secrets, "CACHE_CAPACITY")?.unwrap_or(1000);
+    let name = secrets.get("DICTIONARY_BACKEND");
+    let backend: Box<dyn DictionaryBackend> = match name.as_deref().unwrap_or("daum") {
+        "daum" => with_fallback(
+            Box::new(daum::DaumBackend::new(client, base_url)),
+            offline,
+            Duration::from_secs(ttl),
+            capacity as usize,
+            store,
+        )?,
+        "unihan" => offline,
+        name => return Err(format!("unknown dictionary backend '{name}'").into()),
+    };
+    Ok(Box::new(grade::Graded::new(backend, grades)))
+}
+
+/// `online` behind a persistent cache, falling back to `offline`.
+///
+/// Only `online` is cached, so what `offline` stands in with during an outage
+/// is not served from the cache once `online` is back.
+fn with_fallback(
+    online: Box<dyn DictionaryBackend>,
+    offline: Box<dyn DictionaryBackend>,
+    ttl: Duration,
+    capacity: usize,
+    store: Arc<Store>,
+) -> Result<Box<dyn DictionaryBackend>, Error> {
+    let online = Box::new(coalesce::Coalesced::new(online));
+    let cached = cache::Cached::new(online, ttl, capacity)
+        .persist_to(store)
+        .map_err(|e| format!("failed to load lookup cache: {e}"))?;
+    Ok(Box::new(fallback::Fallback::new(Box::new(cached), offline)))
+}
+
+fn parse_secret(secrets: &SecretStore, key: &str) -> Result<Option<u64>, Error> {
+    secrets
+        .get(key)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|e| format!("invalid {key} '{value}': {e}").into())
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::backend::stub::Flaky;
+    use crate::backend::unihan::UnihanBackend;
+
+    fn hit(headword: &str, reading: &str, gloss: &str) -> SearchHit {
+        SearchHit {
+            id: headword.to_string(),
+            headword: headword.to_string(),
+            reading: Some(reading.to_string()),
+            gloss: Some(gloss.to_string()),
+            source: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn ranks_by_meaning() {
+        let hits = vec![
+            hit("講", "강", "외울 강, 익혀 배울 강"),
+            hit("習", "습", "익힐 습"),
+            hit("學習", "학습", "배워서 익힘"),
+            hit("斅", "효", "가르칠 효, 배울 학"),
+            hit("學", "학", "배울 학"),
+        ];
+        let headwords =
+            |hits: Vec<SearchHit>| hits.into_iter().map(|hit| hit.headword).collect::<Vec<_>>();
+        assert_eq!(
+            headwords(rank_by_meaning("배울", None, hits.clone())),
+            ["學", "斅", "講"]
+        );
+        assert_eq!(headwords(rank_by_meaning("배울", Some("학"), hits)), ["學"]);
+        assert_eq!(
+            headwords(rank_by_meaning(
+                "Learn",
+                None,
+                vec![hit("學", "학", "learning, knowledge; school")]
+            )),
+            ["學"]
+        );
+    }
+
+    #[tokio::test]
+    async fn offline_results_are_not_cached() {
+        let store = Arc::new(Store::in_memory().unwrap());
+        let online = Flaky::default();
+        let down = online.down.clone();
+        down.store(true, Ordering::SeqCst);
+        let backend = with_fallback(
+            Box::new(online),
+            Box::new(UnihanBackend::bundled()),
+            Duration::from_secs(60),
+            10,
+            store.clone(),
+        )
+        .unwrap();
+
+        let entry = backend.lookup("學").await.unwrap().unwrap();
+        assert_eq!(entry.strokes, Some(16));
+        assert!(store.cached("search", 0).unwrap().is_empty());
+        assert!(store.cached("entry", 0).unwrap().is_empty());
+
+        down.store(false, Ordering::SeqCst);
+        let entry = backend.lookup("學").await.unwrap().unwrap();
+        assert_eq!(entry.strokes, None);
+        assert_eq!(store.cached("entry", 0).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use super::{hit_key, normalize, DictionaryBackend, LookupError, RadicalHit, SearchHit};
+use crate::entry::HanjaEntry;
+use crate::store::{CachedRow, Store};
+
+/// [`Store`] cache kinds of the two maps.
+const SEARCHES: &str = "search";
+const ENTRIES: &str = "entry";
+
+/// Remembers search results and entries fetched through `inner`.
+///
+/// Results expire after `ttl`; beyond `capacity` the least recently used one
+/// is evicted. With a [`Store`], every insert is saved to it and reloaded on
+/// startup.
+pub struct Cached {
+    inner: Box<dyn DictionaryBackend>,
+    ttl: Duration,
+    capacity: usize,
+    store: Option<Arc<Store>>,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Keyed by normalized query, or prefixed with `eum:` and `hun:` for
+    /// [`DictionaryBackend::by_reading`] and [`DictionaryBackend::by_meaning`].
+    searches: Lru<Vec<SearchHit>>,
+    /// Keyed by [`hit_key`].
+    entries: Lru<HanjaEntry>,
+}
+
+struct Lru<T> {
+    slots: HashMap<String, Slot<T>>,
+    /// Incremented on every access to order slots by recency.
+    clock: u64,
+}
+
+struct Slot<T> {
+    value: T,
+    fetched_at: SystemTime,
+    used_at: u64,
+}
+
+impl<T> Default for Lru<T> {
+    fn default() -> Self {
+        Self {
+            slots: HashMap::new(),
+            clock: 0,
+        }
+    }
+}
+
+impl<T: Clone> Lru<T> {
+    fn get(&mut self, key: &str, ttl: Duration) -> Option<T> {
+        self.clock += 1;
+        match self.slots.get_mut(key) {
+            Some(slot) if !is_expired(slot.fetched_at, ttl) => {
+                slot.used_at = self.clock;
+                Some(slot.value.clone())
+            }
+            Some(_) => {
+                self.slots.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&mut self, key: String, value: T, fetched_at: SystemTime, capacity: usize) {
+        if self.slots.len() >= capacity && !self.slots.contains_key(&key) {
+            let oldest = self
+                .slots
+                .iter()
+                .min_by_key(|(_, slot)| slot.used_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.slots.remove(&oldest);
+            }
+        }
+        self.clock += 1;
+        self.slots.insert(
+            key,
+            Slot {
+                value,
+                fetched_at,
+                used_at: self.clock,
+            },
+        );
+    }
+
+    /// Insert the unexpired `rows` of a [`Store`], skipping unreadable ones.
+    fn load(&mut self, rows: Vec<CachedRow>, ttl: Duration, capacity: usize)
+    where
+        T: DeserializeOwned,
+    {
+        for row in rows {
+            let fetched_at = UNIX_EPOCH + Duration::from_secs(row.fetched_at);
+            if is_expired(fetched_at, ttl) {
+                continue;
+            }
+            match serde_json::from_str(&row.value) {
+                Ok(value) => self.insert(row.key, value, fetched_at, capacity),
+                Err(e) => tracing::warn!("Ignoring unreadable cached {}: {e}", row.key),
+            }
+        }
+    }
+}
+
+fn is_expired(fetched_at: SystemTime, ttl: Duration) -> bool {
+    fetched_at.elapsed().map_or(true, |age| age >= ttl)
+}
+
+impl Cached {
+    pub fn new(inner: Box<dyn DictionaryBackend>, ttl: Duration, capacity: usize) -> Self {
+        Self {
+            inner,
+            ttl,
+            capacity,
+            store: None,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Persist to `store`, loading whatever unexpired results it already holds.
+    ///
+    /// Rows that cannot be parsed, e.g. from an older version, are ignored.
+    pub fn persist_to(mut self, store: Arc<Store>) -> rusqlite::Result<Self> {
+        let since = SystemTime::now()
+            .checked_sub(self.ttl)
+            .and_then(|since| since.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |since| since.as_secs());
+        let state = self.state.get_mut();
+        let searches = store.cached(SEARCHES, since)?;
+        state.searches.load(searches, self.ttl, self.capacity);
+        let entries = store.cached(ENTRIES, since)?;
+        state.entries.load(entries, self.ttl, self.capacity);
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    /// Cached hits for `key`, or else the non-empty outcome of `search`.
+    async fn search_with(
+        &self,
+        key: String,
+        search: impl Future<Output = Result<Vec<SearchHit>, LookupError>>,
+    ) -> Result<Vec<SearchHit>, LookupError> {
+        if let Some(hits) = self.state.lock().await.searches.get(&key, self.ttl) {
+            return Ok(hits);
+        }
+        let hits = search.await?;
+        if !hits.is_empty() {
+            let mut state = self.state.lock().await;
+            let now = SystemTime::now();
+            self.save(SEARCHES, &key, &hits, now);
+            state.searches.insert(key, hits.clone(), now, self.capacity);
+        }
+        Ok(hits)
+    }
+
+    fn save(&self, kind: &str, key: &str, value: &impl Serialize, fetched_at: SystemTime) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        let result = serde_json::to_string(value)
+            .map_err(|e| e.to_string())
+            .and_then(|value| {
+                let row = CachedRow {
+                    key: key.to_string(),
+                    fetched_at: fetched_at
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs(),
+                    value,
+                };
+                store.cache(kind, &row).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            tracing::warn!("Failed to save cached {key}: {e}");
+        }
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for Cached {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        let key = normalize(query);
+        self.search_with(key.clone(), self.inner.search(&key)).await
+    }
+
+    async fn by_reading(&self, reading: &str) -> Result<Vec<SearchHit>, LookupError> {
+        let reading = normalize(reading);
+        self.search_with(format!("eum:{reading}"), self.inner.by_reading(&reading))
+            .await
+    }
+
+    async fn by_meaning(
+        &self,
+        meaning: &str,
+        reading: Option<&str>,
+    ) -> Result<Vec<SearchHit>, LookupError> {
+        let meaning = normalize(meaning);
+        let key = format!("hun:{meaning}:{}", reading.unwrap_or_default());
+        self.search_with(key, self.inner.by_meaning(&meaning, reading))
+            .await
+    }
+
+    async fn by_radical(&self, number: u8) -> Result<Vec<RadicalHit>, LookupError> {
+        self.inner.by_radical(number).await
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        self.inner.entry(hit).await
+    }
+
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError> {
+        self.inner.supplement(hit, entry).await
+    }
+
+    async fn fetch(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        let key = hit_key(hit);
+        if let Some(entry) = self.state.lock().await.entries.get(&key, self.ttl) {
+            return Ok(entry);
+        }
+        let entry = self.inner.fetch(hit).await?;
+        let mut state = self.state.lock().await;
+        let now = SystemTime::now();
+        self.save(ENTRIES, &key, &entry, now);
+        state.entries.insert(key, entry.clone(), now, self.capacity);
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::backend::stub::Counting;
+
+    fn cached(ttl: Duration, capacity: usize) -> (Cached, Arc<AtomicUsize>) {
+        let inner = Counting::default();
+        let lookups = inner.searches();
+        (Cached::new(Box::new(inner), ttl, capacity), lookups)
+    }
+
+    #[tokio::test]
+    async fn hit_by_normalized_query() {
+        let (cache, lookups) = cached(Duration::from_secs(60), 10);
+        cache.lookup("學").await.unwrap();
+        cache.lookup("  學 ").await.unwrap();
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_refetched() {
+        let (cache, lookups) = cached(Duration::ZERO, 10);
+        cache.lookup("學").await.unwrap();
+        cache.lookup("學").await.unwrap();
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn evicts_least_recently_used() {
+        let (cache, lookups) = cached(Duration::from_secs(60), 2);
+        cache.lookup("學").await.unwrap();
+        cache.lookup("人").await.unwrap();
+        cache.lookup("學").await.unwrap();
+        cache.lookup("大").await.unwrap();
+        assert_eq!(lookups.load(Ordering::SeqCst), 3);
+        cache.lookup("學").await.unwrap();
+        assert_eq!(lookups.load(Ordering::SeqCst), 3);
+        cache.lookup("人").await.unwrap();
+        assert_eq!(lookups.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn survives_restart() {
+        let store = Arc::new(Store::in_memory().unwrap());
+
+        let (cache, _) = cached(Duration::from_secs(60), 10);
+        let cache = cache.persist_to(store.clone()).unwrap();
+        cache.lookup("學").await.unwrap();
+
+        let (cache, lookups) = cached(Duration::from_secs(60), 10);
+        let cache = cache.persist_to(store).unwrap();
+        assert_eq!(cache.lookup("學").await.unwrap().unwrap().reading, "학");
+        assert_eq!(lookups.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn readings_are_cached_apart_from_searches() {
+        let (cache, lookups) = cached(Duration::from_secs(60), 10);
+        cache.by_reading("학").await.unwrap();
+        cache.by_reading(" 학").await.unwrap();
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+        cache.search("학").await.unwrap();
+        assert_eq!(lookups.load(Ordering::SeqCst), 2);
+    }
+}
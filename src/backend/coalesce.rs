@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use tokio::sync::OnceCell;
+
+use super::{hit_key, normalize, DictionaryBackend, LookupError, RadicalHit, SearchHit};
+use crate::entry::HanjaEntry;
+
+/// Shares one search or fetch of `inner` between concurrent callers asking
+/// for the same normalized query or entry.
+pub struct Coalesced {
+    inner: Box<dyn DictionaryBackend>,
+    searches: InFlight<Vec<SearchHit>>,
+    entries: InFlight<HanjaEntry>,
+}
+
+type Call<T> = Arc<OnceCell<Result<T, LookupError>>>;
+
+struct InFlight<T> {
+    calls: Mutex<HashMap<String, Call<T>>>,
+}
+
+impl<T: Clone> InFlight<T> {
+    fn new() -> Self {
+        Self {
+            calls: Mutex::default(),
+        }
+    }
+
+    /// Run `call` unless another caller is already running it for `key`.
+    async fn run<F>(&self, key: String, call: impl FnOnce() -> F) -> Result<T, LookupError>
+    where
+        F: Future<Output = Result<T, LookupError>>,
+    {
+        let cell = self
+            .calls
+            .lock()
+            .unwrap()
+            .entry(key.clone())
+            .or_default()
+            .clone();
+        // If the caller running `call` is cancelled, a waiter takes over.
+        let outcome = cell.get_or_init(call).await.clone();
+
+        let mut calls = self.calls.lock().unwrap();
+        if calls
+            .get(&key)
+            .is_some_and(|current| Arc::ptr_eq(current, &cell))
+        {
+            calls.remove(&key);
+        }
+        outcome
+    }
+
+    #[cfg(test)]
+    fn is_empty(&self) -> bool {
+        self.calls.lock().unwrap().is_empty()
+    }
+}
+
+impl Coalesced {
+    pub fn new(inner: Box<dyn DictionaryBackend>) -> Self {
+        Self {
+            inner,
+            searches: InFlight::new(),
+            entries: InFlight::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for Coalesced {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        let key = normalize(query);
+        self.searches
+            .run(key.clone(), || self.inner.search(&key))
+            .await
+    }
+
+    async fn by_reading(&self, reading: &str) -> Result<Vec<SearchHit>, LookupError> {
+        let reading = normalize(reading);
+        self.searches
+            .run(format!("eum:{reading}"), || self.inner.by_reading(&reading))
+            .await
+    }
+
+    async fn by_meaning(
+        &self,
+        meaning: &str,
+        reading: Option<&str>,
+    ) -> Result<Vec<SearchHit>, LookupError> {
+        let meaning = normalize(meaning);
+        let key = format!("hun:{meaning}:{}", reading.unwrap_or_default());
+        self.searches
+            .run(key, || self.inner.by_meaning(&meaning, reading))
+            .await
+    }
+
+    async fn by_radical(&self, number: u8) -> Result<Vec<RadicalHit>, LookupError> {
+        self.inner.by_radical(number).await
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        self.inner.entry(hit).await
+    }
+
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError> {
+        self.inner.supplement(hit, entry).await
+    }
+
+    async fn fetch(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        self.entries
+            .run(hit_key(hit), || self.inner.fetch(hit))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::backend::stub::Counting;
+
+    #[tokio::test]
+    async fn concurrent_lookups_share_fetch() {
+        let inner = Counting::default();
+        let searches = inner.searches();
+        let backend = Coalesced::new(Box::new(inner));
+
+        let (a, b, c) = tokio::join!(
+            backend.lookup("學"),
+            backend.lookup(" 學"),
+            backend.lookup("人")
+        );
+        assert_eq!(a.unwrap(), b.unwrap());
+        assert_eq!(c.unwrap().unwrap().headword, "人");
+        assert_eq!(searches.load(Ordering::SeqCst), 2);
+        assert!(backend.searches.is_empty());
+        assert!(backend.entries.is_empty());
+
+        backend.lookup("學").await.unwrap();
+        assert_eq!(searches.load(Ordering::SeqCst), 3);
+    }
+}
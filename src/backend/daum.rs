@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use reqwest::RequestBuilder;
+
+use super::{DictionaryBackend, LookupError, SearchHit};
+use crate::daum::{self, Parser};
+use crate::entry::{HanjaEntry, Source};
+
+pub const DEFAULT_BASE_URL: &str = "https://dic.daum.net";
+
+/// How long a request to Daum may take before it fails with
+/// [`LookupError::Timeout`].
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP client giving up on requests after `timeout`.
+pub fn client(timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder().timeout(timeout).build()
+}
+
+/// Scrapes <https://dic.daum.net>, or a stand-in serving the same pages.
+pub struct DaumBackend {
+    client: reqwest::Client,
+    base_url: String,
+    parser: Parser,
+}
+
+impl DaumBackend {
+    pub fn new(client: reqwest::Client, base_url: impl Into<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.into(),
+            parser: Parser::new(),
+        }
+    }
+
+    fn view_url(&self, id: &str) -> String {
+        format!("{}/word/view.do?wordid={id}", self.base_url)
+    }
+
+    async fn text(request: RequestBuilder) -> Result<String, LookupError> {
+        Ok(request.send().await?.error_for_status()?.text().await?)
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for DaumBackend {
+    fn name(&self) -> &'static str {
+        "daum"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        let search_list = Self::text(
+            self.client
+                .get(format!("{}/search.do", self.base_url))
+                .query(&[("dic", "hanja"), ("q", query)]),
+        )
+        .await?;
+        Ok(self.parser.search(&search_list))
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        let view = Self::text(self.client.get(self.view_url(&hit.id))).await?;
+        let reading = self.parser.reading(&view).ok_or(LookupError::Parse {
+            selector: daum::READING,
+        })?;
+        let mut entry = HanjaEntry::new(hit.headword.clone(), reading);
+        (entry.radical, entry.strokes) = self.parser.composition(&view);
+        entry.source = Some(Source {
+            name: "다음 한자사전".to_string(),
+            url: Some(self.view_url(&hit.id)),
+        });
+        Ok(entry)
+    }
+
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError> {
+        let supword = Self::text(
+            self.client
+                .get(format!("{}/word/view_supword.do", self.base_url))
+                .query(&[("suptype", "KUMSUNG_HH"), ("wordid", &hit.id)])
+                .header("Referer", self.view_url(&hit.id)),
+        )
+        .await?;
+        self.parser.supword(&supword, entry);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::Radical;
+
+    /// Checks the parsers against the live site rather than the fixtures.
+    #[tokio::test]
+    #[ignore = "needs network access to dic.daum.net"]
+    async fn live_lookup() {
+        let backend = DaumBackend::new(client(REQUEST_TIMEOUT).unwrap(), DEFAULT_BASE_URL);
+        let hits = backend.search("學").await.unwrap();
+        assert!(hits.iter().any(|hit| hit.headword == "學"), "{hits:?}");
+        let entry = backend.lookup("學").await.unwrap().unwrap();
+        assert!(entry.reading.ends_with('학'), "{entry:?}");
+        assert!(!entry.meanings.is_empty(), "{entry:?}");
+        // `.list_info dt` has only been seen in the fixtures.
+        assert_eq!(entry.strokes, Some(16), "{entry:?}");
+        assert_eq!(
+            entry.radical,
+            Some(Radical {
+                character: "子".to_string(),
+                remaining_strokes: Some(13),
+            })
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::future::Future;
+
+use async_trait::async_trait;
+
+use super::{plausible, DictionaryBackend, LookupError, RadicalHit, SearchHit};
+use crate::entry::HanjaEntry;
+
+/// Uses `secondary` whenever `primary` fails or finds nothing.
+pub struct Fallback {
+    primary: Box<dyn DictionaryBackend>,
+    secondary: Box<dyn DictionaryBackend>,
+}
+
+impl Fallback {
+    pub fn new(primary: Box<dyn DictionaryBackend>, secondary: Box<dyn DictionaryBackend>) -> Self {
+        Self { primary, secondary }
+    }
+
+    /// The backend that produced `hit`.
+    fn owner(&self, hit: &SearchHit) -> &dyn DictionaryBackend {
+        if hit.source == self.secondary.name() {
+            &*self.secondary
+        } else {
+            &*self.primary
+        }
+    }
+
+    /// Hits of `primary` if `useful`, or else those of `secondary`.
+    ///
+    /// When `primary` fails, its error is kept unless `secondary` finds
+    /// something.
+    async fn either<T>(
+        &self,
+        primary: impl Future<Output = Result<Vec<T>, LookupError>>,
+        secondary: impl Future<Output = Result<Vec<T>, LookupError>>,
+        useful: impl Fn(&[T]) -> bool,
+    ) -> Result<Vec<T>, LookupError> {
+        match primary.await {
+            Ok(hits) if useful(&hits) => Ok(hits),
+            Ok(_) => secondary.await,
+            Err(e) => {
+                tracing::warn!("{} search failed: {e}", self.primary.name());
+                match secondary.await {
+                    Ok(hits) if !hits.is_empty() => Ok(hits),
+                    _ => Err(e),
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for Fallback {
+    fn name(&self) -> &'static str {
+        self.primary.name()
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        self.either(
+            self.primary.search(query),
+            self.secondary.search(query),
+            |hits| !plausible(query, hits.to_vec()).is_empty(),
+        )
+        .await
+    }
+
+    async fn by_reading(&self, reading: &str) -> Result<Vec<SearchHit>, LookupError> {
+        self.either(
+            self.primary.by_reading(reading),
+            self.secondary.by_reading(reading),
+            |hits| !hits.is_empty(),
+        )
+        .await
+    }
+
+    async fn by_meaning(
+        &self,
+        meaning: &str,
+        reading: Option<&str>,
+    ) -> Result<Vec<SearchHit>, LookupError> {
+        self.either(
+            self.primary.by_meaning(meaning, reading),
+            self.secondary.by_meaning(meaning, reading),
+            |hits| !hits.is_empty(),
+        )
+        .await
+    }
+
+    async fn by_radical(&self, number: u8) -> Result<Vec<RadicalHit>, LookupError> {
+        self.either(
+            self.primary.by_radical(number),
+            self.secondary.by_radical(number),
+            |hits| !hits.is_empty(),
+        )
+        .await
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        self.owner(hit).entry(hit).await
+    }
+
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError> {
+        self.owner(hit).supplement(hit, entry).await
+    }
+
+    async fn fetch(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        let owner = self.owner(hit);
+        match owner.fetch(hit).await {
+            Err(e) if hit.source == self.primary.name() => {
+                tracing::warn!("{} fetch failed: {e}", self.primary.name());
+                match self.secondary.lookup(&hit.headword).await {
+                    Ok(Some(entry)) => Ok(entry),
+                    _ => Err(e),
+                }
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::stub::Unreachable;
+    use crate::backend::unihan::UnihanBackend;
+
+    fn backend() -> Fallback {
+        Fallback::new(Box::new(Unreachable), Box::new(UnihanBackend::bundled()))
+    }
+
+    #[tokio::test]
+    async fn falls_back_on_error() {
+        let backend = backend();
+        let entry = backend.lookup("學").await.unwrap().unwrap();
+        assert_eq!(entry.reading, "학");
+
+        let hit = backend.search("學").await.unwrap().remove(0);
+        assert_eq!(hit.source, "unihan");
+        assert_eq!(backend.entry(&hit).await.unwrap().strokes, Some(16));
+
+        let hits = backend.by_reading("학").await.unwrap();
+        assert_eq!(hits[0].headword, "學");
+
+        let hits = backend.by_radical(39).await.unwrap();
+        assert_eq!(hits[0].hit.headword, "子");
+    }
+
+    #[tokio::test]
+    async fn keeps_primary_error_without_fallback_result() {
+        let error = backend().lookup("學校").await.unwrap_err();
+        assert!(matches!(error, LookupError::Timeout));
+    }
+}
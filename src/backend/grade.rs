@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{DictionaryBackend, LookupError, RadicalHit, SearchHit};
+use crate::entry::HanjaEntry;
+use crate::grade::Grades;
+
+/// Annotates entries from `inner` with their [`Grade`](crate::grade::Grade).
+///
+/// Sits outside the cache so that entries cached before a table update are
+/// graded too.
+pub struct Graded {
+    inner: Box<dyn DictionaryBackend>,
+    grades: Arc<Grades>,
+}
+
+impl Graded {
+    pub fn new(inner: Box<dyn DictionaryBackend>, grades: Arc<Grades>) -> Self {
+        Self { inner, grades }
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for Graded {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        self.inner.search(query).await
+    }
+
+    async fn by_reading(&self, reading: &str) -> Result<Vec<SearchHit>, LookupError> {
+        self.inner.by_reading(reading).await
+    }
+
+    async fn by_meaning(
+        &self,
+        meaning: &str,
+        reading: Option<&str>,
+    ) -> Result<Vec<SearchHit>, LookupError> {
+        self.inner.by_meaning(meaning, reading).await
+    }
+
+    async fn by_radical(&self, number: u8) -> Result<Vec<RadicalHit>, LookupError> {
+        self.inner.by_radical(number).await
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        self.inner.entry(hit).await
+    }
+
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError> {
+        self.inner.supplement(hit, entry).await
+    }
+
+    async fn fetch(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        let mut entry = self.inner.fetch(hit).await?;
+        entry.grade = self.grades.get(&entry.headword);
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::stub::Counting;
+    use crate::grade::Level;
+
+    #[tokio::test]
+    async fn grades_fetched_entries() {
+        let backend = Graded::new(Box::new(Counting::default()), Arc::new(Grades::bundled()));
+        let entry = backend.lookup("學").await.unwrap().unwrap();
+        assert_eq!(entry.grade.unwrap().level, Some(Level::Grade8));
+        assert_eq!(backend.lookup("鶴").await.unwrap().unwrap().grade, None);
+    }
+}
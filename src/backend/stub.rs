@@ -0,0 +1,124 @@
+//! Backends for tests.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{DictionaryBackend, LookupError, SearchHit};
+use crate::entry::HanjaEntry;
+
+/// Answers every query with a fresh entry, counting searches.
+#[derive(Default)]
+pub struct Counting {
+    pub searches: Arc<AtomicUsize>,
+}
+
+impl Counting {
+    pub fn searches(&self) -> Arc<AtomicUsize> {
+        self.searches.clone()
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for Counting {
+    fn name(&self) -> &'static str {
+        "counting"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        self.searches.fetch_add(1, Ordering::SeqCst);
+        // Give concurrent callers a chance to run while the "request" is in flight.
+        for _ in 0..4 {
+            tokio::task::yield_now().await;
+        }
+        Ok(vec![SearchHit {
+            id: query.trim().to_string(),
+            headword: query.trim().to_string(),
+            reading: Some("학".to_string()),
+            gloss: None,
+            source: self.name().to_string(),
+        }])
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        Ok(HanjaEntry::new(hit.headword.clone(), "학".to_string()))
+    }
+
+    async fn supplement(
+        &self,
+        _hit: &SearchHit,
+        _entry: &mut HanjaEntry,
+    ) -> Result<(), LookupError> {
+        Ok(())
+    }
+}
+
+/// Fails every request as if the dictionary timed out.
+pub struct Unreachable;
+
+#[async_trait]
+impl DictionaryBackend for Unreachable {
+    fn name(&self) -> &'static str {
+        "unreachable"
+    }
+
+    async fn search(&self, _query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        Err(LookupError::Timeout)
+    }
+
+    async fn entry(&self, _hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        Err(LookupError::Timeout)
+    }
+
+    async fn supplement(
+        &self,
+        _hit: &SearchHit,
+        _entry: &mut HanjaEntry,
+    ) -> Result<(), LookupError> {
+        Err(LookupError::Timeout)
+    }
+}
+
+/// [`Counting`] that times out while `down` is set.
+#[derive(Default)]
+pub struct Flaky {
+    pub down: Arc<AtomicBool>,
+    inner: Counting,
+}
+
+impl Flaky {
+    fn check(&self) -> Result<(), LookupError> {
+        if self.down.load(Ordering::SeqCst) {
+            Err(LookupError::Timeout)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for Flaky {
+    fn name(&self) -> &'static str {
+        "flaky"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        self.check()?;
+        let mut hits = self.inner.search(query).await?;
+        for hit in &mut hits {
+            hit.source = self.name().to_string();
+        }
+        Ok(hits)
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        self.check()?;
+        self.inner.entry(hit).await
+    }
+
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError> {
+        self.check()?;
+        self.inner.supplement(hit, entry).await
+    }
+}
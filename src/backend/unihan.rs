@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use super::{rank_by_meaning, DictionaryBackend, LookupError, RadicalHit, SearchHit};
+use crate::entry::{HanjaEntry, MeaningGroup, Radical, Source};
+use crate::radical;
+use crate::unihan::{Record, UnihanIndex};
+
+/// Answers from a local [`UnihanIndex`] without any network access.
+pub struct UnihanBackend {
+    index: Arc<UnihanIndex>,
+}
+
+impl UnihanBackend {
+    pub fn new(index: Arc<UnihanIndex>) -> Self {
+        Self { index }
+    }
+
+    /// Backend over the bundled subset, for tests that must not touch the network.
+    #[cfg(test)]
+    pub fn bundled() -> Self {
+        Self::new(Arc::new(UnihanIndex::bundled()))
+    }
+
+    fn hit(&self, character: char, record: &Record) -> SearchHit {
+        SearchHit {
+            id: character.to_string(),
+            headword: character.to_string(),
+            reading: Some(record.readings.join(", ")),
+            gloss: record.definition.clone(),
+            source: self.name().to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl DictionaryBackend for UnihanBackend {
+    fn name(&self) -> &'static str {
+        "unihan"
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchHit>, LookupError> {
+        let mut chars = query.trim().chars();
+        let (Some(character), None) = (chars.next(), chars.next()) else {
+            return Ok(Vec::new());
+        };
+        Ok(self
+            .index
+            .get(character)
+            .map(|record| self.hit(character, record))
+            .into_iter()
+            .collect())
+    }
+
+    async fn by_reading(&self, reading: &str) -> Result<Vec<SearchHit>, LookupError> {
+        Ok(self
+            .index
+            .with_reading(reading.trim())
+            .into_iter()
+            .map(|(character, record)| self.hit(character, record))
+            .collect())
+    }
+
+    async fn by_radical(&self, number: u8) -> Result<Vec<RadicalHit>, LookupError> {
+        Ok(self
+            .index
+            .with_radical(number)
+            .into_iter()
+            .filter_map(|(character, record)| {
+                Some(RadicalHit {
+                    remaining_strokes: record.radical?.1,
+                    hit: self.hit(character, record),
+                })
+            })
+            .collect())
+    }
+
+    /// Match `meaning` against the English `kDefinition`; Unihan has no
+    /// Korean glosses.
+    async fn by_meaning(
+        &self,
+        meaning: &str,
+        reading: Option<&str>,
+    ) -> Result<Vec<SearchHit>, LookupError> {
+        let hits = self
+            .index
+            .characters()
+            .into_iter()
+            .map(|(character, record)| self.hit(character, record))
+            .collect();
+        Ok(rank_by_meaning(meaning, reading, hits))
+    }
+
+    async fn entry(&self, hit: &SearchHit) -> Result<HanjaEntry, LookupError> {
+        let record = hit
+            .id
+            .chars()
+            .next()
+            .and_then(|character| self.index.get(character))
+            .ok_or(LookupError::NotFound)?;
+        let mut entry = HanjaEntry::new(hit.headword.clone(), record.readings.join(", "));
+        entry.radical = record.radical.and_then(|(number, remaining)| {
+            Some(Radical {
+                character: radical::character(number)?.to_string(),
+                remaining_strokes: Some(remaining),
+            })
+        });
+        entry.strokes = record.strokes;
+        entry.source = Some(Source {
+            name: "Unicode Unihan Database".to_string(),
+            url: hit.id.chars().next().map(|character| {
+                format!(
+                    "https://www.unicode.org/cgi-bin/GetUnihanData.pl?codepoint={:X}",
+                    u32::from(character)
+                )
+            }),
+        });
+        Ok(entry)
+    }
+
+    async fn supplement(&self, hit: &SearchHit, entry: &mut HanjaEntry) -> Result<(), LookupError> {
+        let definition = hit
+            .id
+            .chars()
+            .next()
+            .and_then(|character| self.index.get(character))
+            .and_then(|record| record.definition.as_deref());
+        if let Some(definition) = definition {
+            entry.meanings.push(MeaningGroup {
+                part_of_speech: None,
+                senses: definition
+                    .split(';')
+                    .map(|s| s.trim().to_string())
+                    .collect(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn lookup_bundled() {
+        let backend = UnihanBackend::bundled();
+        let entry = backend.lookup("學").await.unwrap().unwrap();
+        assert_eq!(entry.reading, "학");
+        assert_eq!(entry.strokes, Some(16));
+        assert_eq!(
+            entry.radical,
+            Some(Radical {
+                character: "子".to_string(),
+                remaining_strokes: Some(13),
+            })
+        );
+        assert_eq!(entry.meanings[0].senses, ["learning, knowledge", "school"]);
+        assert_eq!(backend.lookup("學校").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn characters_by_reading() {
+        let backend = UnihanBackend::bundled();
+        let hits = backend.by_reading("수").await.unwrap();
+        let headwords = hits
+            .iter()
+            .map(|hit| hit.headword.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(headwords, ["手", "水", "修"]);
+        assert_eq!(hits[0].reading.as_deref(), Some("수"));
+    }
+
+    #[tokio::test]
+    async fn characters_by_meaning() {
+        let backend = UnihanBackend::bundled();
+        let hits = backend.by_meaning("school", None).await.unwrap();
+        assert_eq!(hits[0].headword, "校");
+        assert!(hits.iter().any(|hit| hit.headword == "學"));
+        assert!(backend
+            .by_meaning("school", Some("교"))
+            .await
+            .unwrap()
+            .iter()
+            .all(|hit| hit.headword == "校"));
+    }
+}
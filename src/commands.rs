@@ -0,0 +1,495 @@
+//! Bot commands.
+
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use poise::serenity_prelude as serenity;
+use poise::{CreateReply, ReplyHandle};
+
+use crate::backend::{LookupError, SearchHit};
+use crate::entry::HanjaEntry;
+use crate::render::{self, truncate};
+use crate::store::Word;
+use crate::{Context, Data, Error};
+
+pub mod eum;
+pub mod hanja;
+pub mod hun;
+pub mod idiom;
+pub mod quiz;
+pub mod radical;
+pub mod read;
+pub mod review;
+pub mod tohanja;
+pub mod wordbook;
+
+pub use eum::eum;
+pub use hanja::hanja;
+pub use hun::hun;
+pub use idiom::idiom;
+pub use quiz::quiz;
+pub use radical::radical;
+pub use read::{passive, read, read_hanja};
+pub use review::review;
+pub use tohanja::tohanja;
+pub use wordbook::wordbook;
+
+/// Prefix of text commands.
+pub const PREFIX: &str = "gaji ";
+
+#[poise::command(prefix_command)]
+pub async fn ping(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.say("Pong!").await?;
+    Ok(())
+}
+
+/// Invocation data pointing at the "Searching for …" message of a prefix command.
+#[derive(Clone, Copy)]
+struct Placeholder {
+    channel_id: serenity::ChannelId,
+    message_id: serenity::MessageId,
+}
+
+/// Post the loading message that [`on_error`] replaces if the command fails.
+pub async fn placeholder<'a>(ctx: Context<'a>, query: &str) -> Result<ReplyHandle<'a>, Error> {
+    let handle = ctx
+        .reply(format!(
+            "Searching for {} <a:Loading:1363125483667193998>",
+            query
+        ))
+        .await?;
+    if let Context::Prefix(_) = ctx {
+        let message = handle.message().await?;
+        ctx.set_invocation_data(Placeholder {
+            channel_id: message.channel_id,
+            message_id: message.id,
+        })
+        .await;
+    }
+    Ok(handle)
+}
+
+/// Where a lookup shows its progress and result.
+///
+/// Commands reply through [`Discord`]; tests record what would be shown.
+#[async_trait]
+pub trait ReplySink: Send {
+    /// Say that `query` is being looked up.
+    async fn searching(&mut self, query: &str) -> Result<(), Error>;
+
+    /// The only hit, or the one the user picks from `hits`, if any.
+    async fn choose(
+        &mut self,
+        prompt: &str,
+        hits: &[SearchHit],
+    ) -> Result<Option<SearchHit>, Error>;
+
+    /// Show `entry` in place of the placeholder.
+    async fn entry(&mut self, entry: &HanjaEntry) -> Result<(), Error>;
+}
+
+/// Replies to the invoking user through a [`placeholder`].
+pub struct Discord<'a> {
+    ctx: Context<'a>,
+    handle: Option<ReplyHandle<'a>>,
+}
+
+impl<'a> Discord<'a> {
+    pub fn new(ctx: Context<'a>) -> Self {
+        Self { ctx, handle: None }
+    }
+
+    fn handle(&self) -> Result<&ReplyHandle<'a>, Error> {
+        Ok(self
+            .handle
+            .as_ref()
+            .ok_or("the placeholder has not been sent")?)
+    }
+}
+
+#[async_trait]
+impl ReplySink for Discord<'_> {
+    async fn searching(&mut self, query: &str) -> Result<(), Error> {
+        self.handle = Some(placeholder(self.ctx, query).await?);
+        Ok(())
+    }
+
+    async fn choose(
+        &mut self,
+        prompt: &str,
+        hits: &[SearchHit],
+    ) -> Result<Option<SearchHit>, Error> {
+        choose(self.ctx, self.handle()?, prompt, hits).await
+    }
+
+    async fn entry(&mut self, entry: &HanjaEntry) -> Result<(), Error> {
+        show_entry(self.ctx, self.handle()?, entry).await
+    }
+}
+
+/// `hits`, failing with [`LookupError::NotFound`] if there are none.
+pub fn non_empty(hits: Vec<SearchHit>) -> Result<Vec<SearchHit>, LookupError> {
+    if hits.is_empty() {
+        Err(LookupError::NotFound)
+    } else {
+        Ok(hits)
+    }
+}
+
+/// Most related characters offered as buttons, leaving room for Save and a
+/// row for paging.
+const RELATED_BUTTONS: usize = 19;
+
+/// Replace `handle` with `entry` as embeds, or as plain text where the bot
+/// may not embed links.
+///
+/// Related characters get buttons that show their own entry in its place,
+/// and a Save button adds the entry shown to the user's wordbook.
+pub async fn show_entry(
+    ctx: Context<'_>,
+    handle: &ReplyHandle<'_>,
+    entry: &HanjaEntry,
+) -> Result<(), Error> {
+    let mut entry = entry.clone();
+    loop {
+        let mut related = Vec::new();
+        for character in entry
+            .relations
+            .iter()
+            .flat_map(|relation| &relation.characters)
+        {
+            if *character != entry.headword && !related.contains(character) {
+                related.push(character.clone());
+            }
+        }
+        related.truncate(RELATED_BUTTONS);
+        let mut actions = related.clone();
+        actions.push("💾 Save".to_string());
+
+        let Some(i) = browse(ctx, handle, &entry, &actions).await? else {
+            return Ok(());
+        };
+        if i == related.len() {
+            save(ctx, &entry).await?;
+            continue;
+        }
+        entry = ctx
+            .data()
+            .backend
+            .lookup(&related[i])
+            .await?
+            .ok_or(LookupError::NotFound)?;
+    }
+}
+
+/// Add `entry` to the wordbook of the invoking user.
+async fn save(ctx: Context<'_>, entry: &HanjaEntry) -> Result<(), Error> {
+    let word = Word {
+        headword: entry.headword.clone(),
+        reading: entry.reading.clone(),
+    };
+    let content = if ctx.data().store.add_word(ctx.author().id, &word)? {
+        format!("Saved {} to your wordbook.", entry.headword)
+    } else {
+        format!("{} is already in your wordbook.", entry.headword)
+    };
+    ctx.send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(())
+}
+
+/// Page through `entry` until the buttons expire or one of `actions` is
+/// pressed, returning its index.
+async fn browse(
+    ctx: Context<'_>,
+    handle: &ReplyHandle<'_>,
+    entry: &HanjaEntry,
+    actions: &[String],
+) -> Result<Option<usize>, Error> {
+    let emoji = &ctx.data().emoji;
+    let embeds = render::embeds(entry, emoji)
+        .into_iter()
+        .map(|embed| CreateReply::default().content("").embed(embed))
+        .collect::<Vec<_>>();
+    match navigate(ctx, handle, 0, embeds.len(), actions, |page| {
+        std::future::ready(Ok(embeds[page].clone()))
+    })
+    .await
+    {
+        Err(e) if is_missing_permissions(&e) => {
+            let messages = render::messages(entry, emoji)
+                .into_iter()
+                .map(|message| CreateReply::default().content(message))
+                .collect::<Vec<_>>();
+            navigate(ctx, handle, 0, messages.len(), actions, |page| {
+                std::future::ready(Ok(messages[page].clone()))
+            })
+            .await
+        }
+        result => result,
+    }
+}
+
+/// Discord's JSON error code for a request the bot lacks permissions for.
+const MISSING_PERMISSIONS: isize = 50013;
+
+fn is_missing_permissions(error: &Error) -> bool {
+    matches!(
+        error.downcast_ref::<serenity::Error>(),
+        Some(serenity::Error::Http(serenity::HttpError::UnsuccessfulRequest(response)))
+            if response.error.code == MISSING_PERMISSIONS
+    )
+}
+
+/// Show `count` pages on `handle` with Previous/Next buttons for the
+/// invoking user, starting from `page` and rendering each when it is shown.
+///
+/// Each of `actions` gets a button of its own; pressing one returns its index
+/// and leaves the buttons in place for the caller to replace. Otherwise the
+/// buttons are removed after two minutes without a press.
+pub async fn navigate<F>(
+    ctx: Context<'_>,
+    handle: &ReplyHandle<'_>,
+    mut page: usize,
+    count: usize,
+    actions: &[String],
+    mut render: impl FnMut(usize) -> F,
+) -> Result<Option<usize>, Error>
+where
+    F: Future<Output = Result<CreateReply, Error>>,
+{
+    let prev = format!("{}:prev", ctx.id());
+    let next = format!("{}:next", ctx.id());
+    let action_ids = (0..actions.len())
+        .map(|i| format!("{}:action:{i}", ctx.id()))
+        .collect::<Vec<_>>();
+    let buttons = |page: usize| {
+        let mut rows = Vec::new();
+        if count > 1 {
+            rows.push(serenity::CreateActionRow::Buttons(vec![
+                serenity::CreateButton::new(&prev)
+                    .label("◀")
+                    .style(serenity::ButtonStyle::Secondary)
+                    .disabled(page == 0),
+                serenity::CreateButton::new(&next)
+                    .label("▶")
+                    .style(serenity::ButtonStyle::Secondary)
+                    .disabled(page + 1 == count),
+            ]));
+        }
+        let actions = action_ids.iter().zip(actions).map(|(id, label)| {
+            serenity::CreateButton::new(id)
+                .label(truncate(label, 80))
+                .style(serenity::ButtonStyle::Secondary)
+        });
+        for row in actions.collect::<Vec<_>>().chunks(5) {
+            rows.push(serenity::CreateActionRow::Buttons(row.to_vec()));
+        }
+        rows
+    };
+
+    let mut current = render(page).await?;
+    handle
+        .edit(ctx, current.clone().components(buttons(page)))
+        .await?;
+    if count < 2 && actions.is_empty() {
+        return Ok(None);
+    }
+    let mut custom_ids = vec![prev.clone(), next.clone()];
+    custom_ids.extend(action_ids.iter().cloned());
+    while let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .custom_ids(custom_ids.clone())
+        .timeout(Duration::from_secs(120))
+        .await
+    {
+        press
+            .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+            .await?;
+        if let Some(i) = action_ids.iter().position(|id| *id == press.data.custom_id) {
+            return Ok(Some(i));
+        }
+        if press.data.custom_id == next {
+            page = (page + 1).min(count - 1);
+        } else {
+            page = page.saturating_sub(1);
+        }
+        current = render(page).await?;
+        handle
+            .edit(ctx, current.clone().components(buttons(page)))
+            .await?;
+    }
+    handle.edit(ctx, current.components(vec![])).await?;
+    Ok(None)
+}
+
+/// The only hit, or the one the invoking user picks from `hits`.
+///
+/// Returns `None`, after saying so on `handle`, if nothing was picked.
+pub async fn choose(
+    ctx: Context<'_>,
+    handle: &ReplyHandle<'_>,
+    prompt: &str,
+    hits: &[SearchHit],
+) -> Result<Option<SearchHit>, Error> {
+    if let [hit] = hits {
+        return Ok(Some(hit.clone()));
+    }
+    let hit = pick(ctx, handle, prompt, hits).await?;
+    if hit.is_none() {
+        handle
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content("No entry was selected")
+                    .components(vec![]),
+            )
+            .await?;
+    }
+    Ok(hit)
+}
+
+/// Let the invoking user choose one of `hits` from a select menu on `handle`.
+///
+/// Returns `None` if nothing was chosen within a minute.
+pub async fn pick(
+    ctx: Context<'_>,
+    handle: &ReplyHandle<'_>,
+    prompt: &str,
+    hits: &[SearchHit],
+) -> Result<Option<SearchHit>, Error> {
+    let custom_id = format!("{}:pick", ctx.id());
+    let options = hits
+        .iter()
+        .enumerate()
+        .take(25)
+        .map(|(i, hit)| {
+            let label = match &hit.reading {
+                Some(reading) => format!("{} ({reading})", hit.headword),
+                None => hit.headword.clone(),
+            };
+            let option =
+                serenity::CreateSelectMenuOption::new(truncate(&label, 100), i.to_string());
+            match &hit.gloss {
+                Some(gloss) => option.description(truncate(gloss, 100)),
+                None => option,
+            }
+        })
+        .collect();
+    let menu = serenity::CreateSelectMenu::new(
+        &custom_id,
+        serenity::CreateSelectMenuKind::String { options },
+    );
+    handle
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(prompt)
+                .components(vec![serenity::CreateActionRow::SelectMenu(menu)]),
+        )
+        .await?;
+
+    let Some(interaction) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .custom_ids(vec![custom_id])
+        .timeout(Duration::from_secs(60))
+        .await
+    else {
+        return Ok(None);
+    };
+    interaction
+        .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+        .await?;
+    let serenity::ComponentInteractionDataKind::StringSelect { values } = &interaction.data.kind
+    else {
+        return Ok(None);
+    };
+    Ok(values
+        .first()
+        .and_then(|value| value.parse::<usize>().ok())
+        .and_then(|i| hits.get(i))
+        .cloned())
+}
+
+/// Handle gateway events other than commands.
+pub async fn on_event(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    data: &Data,
+) -> Result<(), Error> {
+    if let serenity::FullEvent::Message { new_message } = event {
+        read::on_message(ctx, data, new_message).await?;
+    }
+    Ok(())
+}
+
+/// Turn command errors into a message instead of leaving the placeholder behind.
+pub async fn on_error(error: poise::FrameworkError<'_, Data, Error>) {
+    let poise::FrameworkError::Command { error, ctx, .. } = error else {
+        if let Err(e) = poise::builtins::on_error(error).await {
+            tracing::error!("Error while handling error: {e}");
+        }
+        return;
+    };
+    match error.downcast_ref::<LookupError>() {
+        Some(LookupError::NotFound) => {}
+        Some(e) => tracing::warn!("{} lookup failed: {e}", ctx.command().name),
+        None => tracing::error!("{} failed: {error}", ctx.command().name),
+    }
+    if let Err(e) = report(ctx, error_message(&error, ctx.locale())).await {
+        tracing::error!("Failed to report error: {e}");
+    }
+}
+
+/// What [`on_error`] tells a user of `locale` about `error`.
+pub fn error_message(error: &Error, locale: Option<&str>) -> String {
+    match error.downcast_ref::<LookupError>() {
+        Some(e) => e.user_message(locale),
+        None if locale.is_some_and(|locale| locale.starts_with("ko")) => {
+            "오류가 발생했습니다.".to_string()
+        }
+        None => "Something went wrong.".to_string(),
+    }
+}
+
+/// Replace the placeholder, or whatever a command has shown in its place,
+/// with `content`, dropping embeds and buttons that no longer work.
+async fn report(ctx: Context<'_>, content: String) -> Result<(), Error> {
+    let placeholder = ctx.invocation_data::<Placeholder>().await.map(|p| *p);
+    match (ctx, placeholder) {
+        (Context::Prefix(_), Some(placeholder)) => {
+            placeholder
+                .channel_id
+                .edit_message(
+                    ctx,
+                    placeholder.message_id,
+                    serenity::EditMessage::new()
+                        .content(content)
+                        .embeds(vec![])
+                        .components(vec![]),
+                )
+                .await?;
+        }
+        (Context::Application(actx), _)
+            if actx.has_sent_initial_response.load(Ordering::SeqCst) =>
+        {
+            actx.interaction
+                .edit_response(
+                    ctx,
+                    serenity::EditInteractionResponse::new()
+                        .content(content)
+                        .embeds(vec![])
+                        .components(vec![]),
+                )
+                .await?;
+        }
+        _ => {
+            ctx.reply(content).await?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use super::{choose, non_empty, placeholder, show_entry};
+use crate::backend::{DictionaryBackend, LookupError, SearchHit};
+use crate::grade::Filter;
+use crate::text::is_syllable;
+use crate::{Context, Error};
+
+/// Find hanja by their hangul reading
+///
+/// Characters are grouped by 한자능력검정시험 level, easiest first, and can be
+/// limited to a level such as `6급` or to the 중학교/고등학교 기초한자.
+#[poise::command(
+    prefix_command,
+    slash_command,
+    track_edits,
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn eum(ctx: Context<'_>, reading: String, level: Option<Filter>) -> Result<(), Error> {
+    let reading = reading.trim();
+    if !is_syllable(reading) {
+        ctx.say("Please give a single hangul syllable, e.g. `학`.")
+            .await?;
+        return Ok(());
+    }
+    let result = placeholder(ctx, reading).await?;
+    let backend = &*ctx.data().backend;
+    let grades = &ctx.data().grades;
+    let mut hits = characters(backend, reading).await?;
+    if let Some(filter) = level {
+        hits.retain(|hit| grades.admits(filter, &hit.headword));
+    }
+    if hits.is_empty() {
+        return Err(LookupError::NotFound.into());
+    }
+    hits.sort_by_key(|hit| {
+        let level = grades.get(&hit.headword).and_then(|grade| grade.level);
+        (level.is_none(), level)
+    });
+    let prompt = format!("Hanja read as {reading}:");
+    let Some(hit) = choose(ctx, &result, &prompt, &hits).await? else {
+        return Ok(());
+    };
+    let entry = backend.fetch(&hit).await?;
+    show_entry(ctx, &result, &entry).await
+}
+
+/// Characters read as `reading`.
+pub async fn characters(
+    backend: &dyn DictionaryBackend,
+    reading: &str,
+) -> Result<Vec<SearchHit>, LookupError> {
+    non_empty(backend.by_reading(reading).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::mock_daum::MockDaum;
+
+    const SEARCH_EUM_HAK: &str = include_str!("../../fixtures/daum/search_eum_hak.html");
+    const SEARCH_NO_RESULT: &str = include_str!("../../fixtures/daum/search_no_result.html");
+
+    #[tokio::test]
+    async fn characters_from_daum() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_EUM_HAK)]).await;
+        let backend = server.backend();
+
+        let hits = characters(&backend, "학").await.unwrap();
+        let headwords = hits
+            .iter()
+            .map(|hit| hit.headword.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(headwords, ["學", "鶴", "虐"]);
+        assert_eq!(hits[1].gloss.as_deref(), Some("학 학, 두루미 학"));
+        assert_eq!(server.requests(), ["/search.do?dic=hanja&q=%ED%95%99"]);
+    }
+
+    #[tokio::test]
+    async fn characters_no_result() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_NO_RESULT)]).await;
+        let backend = server.backend();
+
+        assert!(matches!(
+            characters(&backend, "뷁").await,
+            Err(LookupError::NotFound)
+        ));
+    }
+}
@@ -0,0 +1,332 @@
+use futures::future::join_all;
+
+use super::{non_empty, Discord, ReplySink};
+use crate::backend::{plausible, DictionaryBackend, LookupError, SearchHit};
+use crate::entry::{CharacterGloss, HanjaEntry};
+use crate::text::{is_hanja, sound};
+use crate::{Context, Error};
+
+/// Search hanja
+#[poise::command(
+    prefix_command,
+    slash_command,
+    track_edits,
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn hanja(ctx: Context<'_>, hanja: String) -> Result<(), Error> {
+    lookup(&*ctx.data().backend, &mut Discord::new(ctx), &hanja).await
+}
+
+/// Look `hanja` up in `backend` and show the entry through `reply`.
+///
+/// Compound words missing from the dictionary are still shown with the
+/// readings of their characters.
+pub async fn lookup(
+    backend: &dyn DictionaryBackend,
+    reply: &mut impl ReplySink,
+    hanja: &str,
+) -> Result<(), Error> {
+    reply.searching(hanja).await?;
+    let is_compound = hanja.chars().filter(|&c| is_hanja(c)).count() > 1;
+    let entry = match candidates(backend, hanja).await {
+        Ok(hits) => {
+            let Some(hit) = reply.choose("Which entry do you mean?", &hits).await? else {
+                return Ok(());
+            };
+            Some(backend.fetch(&hit).await?)
+        }
+        Err(LookupError::NotFound) if is_compound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let characters = if is_compound {
+        breakdown(backend, hanja).await
+    } else {
+        Vec::new()
+    };
+
+    let mut entry = match entry {
+        Some(entry) => entry,
+        None if !characters.is_empty() => {
+            let reading = characters
+                .iter()
+                .map(|gloss| sound(&gloss.reading))
+                .collect();
+            HanjaEntry::new(hanja.trim().to_string(), reading)
+        }
+        None => return Err(LookupError::NotFound.into()),
+    };
+    entry.characters = characters;
+    reply.entry(&entry).await
+}
+
+/// Reading and first sense of each hanja in `word`, skipping characters
+/// that cannot be looked up.
+pub async fn breakdown(backend: &dyn DictionaryBackend, word: &str) -> Vec<CharacterGloss> {
+    let lookups = word.chars().filter(|&c| is_hanja(c)).map(|c| async move {
+        match backend.lookup(&c.to_string()).await {
+            Ok(Some(entry)) => Some(CharacterGloss {
+                character: c.to_string(),
+                meaning: entry
+                    .meanings
+                    .iter()
+                    .flat_map(|group| group.senses.first())
+                    .next()
+                    .cloned(),
+                reading: entry.reading,
+            }),
+            Ok(None) => None,
+            Err(e) => {
+                tracing::warn!("Failed to look up {c} in {word}: {e}");
+                None
+            }
+        }
+    });
+    join_all(lookups).await.into_iter().flatten().collect()
+}
+
+/// Plausible entries for `query`.
+pub async fn candidates(
+    backend: &dyn DictionaryBackend,
+    query: &str,
+) -> Result<Vec<SearchHit>, LookupError> {
+    non_empty(plausible(query, backend.search(query).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+
+    use crate::backend::unihan::UnihanBackend;
+    use crate::commands::error_message;
+    use crate::mock_daum::{MockDaum, NO_RESPONSE};
+    use crate::render;
+
+    const SEARCH_HAK: &str = include_str!("../../fixtures/daum/search_hak.html");
+    const SEARCH_RAK: &str = include_str!("../../fixtures/daum/search_rak.html");
+    const SEARCH_NO_RESULT: &str = include_str!("../../fixtures/daum/search_no_result.html");
+    const VIEW_HAK: &str = include_str!("../../fixtures/daum/view_hak.html");
+    const VIEW_MALFORMED: &str = include_str!("../../fixtures/daum/view_malformed.html");
+    const SUPWORD_HAK: &str = include_str!("../../fixtures/daum/supword_hak.html");
+
+    /// Keeps what a lookup would show, picking the first of several hits.
+    #[derive(Default)]
+    struct Recorder {
+        shown: Vec<String>,
+    }
+
+    #[async_trait]
+    impl ReplySink for Recorder {
+        async fn searching(&mut self, query: &str) -> Result<(), Error> {
+            self.shown.push(format!("Searching for {query}"));
+            Ok(())
+        }
+
+        async fn choose(
+            &mut self,
+            prompt: &str,
+            hits: &[SearchHit],
+        ) -> Result<Option<SearchHit>, Error> {
+            if hits.len() > 1 {
+                self.shown.push(prompt.to_string());
+            }
+            Ok(hits.first().cloned())
+        }
+
+        async fn entry(&mut self, entry: &HanjaEntry) -> Result<(), Error> {
+            self.shown
+                .push(render::message(entry, &render::Emoji::default()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn lookup_from_daum() {
+        let server = MockDaum::start(vec![
+            ("/search.do", SEARCH_HAK),
+            ("/word/view.do?wordid=hjdic_0001234", VIEW_HAK),
+            ("/word/view_supword.do", SUPWORD_HAK),
+        ])
+        .await;
+        let backend = server.backend();
+
+        let mut reply = Recorder::default();
+        lookup(&backend, &mut reply, "學").await.unwrap();
+        assert_eq!(reply.shown.len(), 2);
+        assert_eq!(reply.shown[0], "Searching for 學");
+        let content = &reply.shown[1];
+        assert!(content.starts_with("# 學\n**배울 학**\n[동사]\n1 배우다. 글을 읽고 익히다.\n"));
+        assert!(content.contains("> 學而時習之(학이시습지) 《論語》\n"));
+        assert!(content.ends_with("-# 부수 子 · 총 16획\n"));
+        assert_eq!(
+            server.requests(),
+            [
+                "/search.do?dic=hanja&q=%E5%AD%B8",
+                "/word/view.do?wordid=hjdic_0001234",
+                "/word/view_supword.do?suptype=KUMSUNG_HH&wordid=hjdic_0001234",
+            ]
+        );
+
+        let hits = candidates(&backend, "學").await.unwrap();
+        let entry = backend.fetch(&hits[0]).await.unwrap();
+        assert_eq!(
+            entry.source.as_ref().unwrap().url.as_deref().unwrap(),
+            format!("{}/word/view.do?wordid=hjdic_0001234", server.base_url())
+        );
+    }
+
+    #[tokio::test]
+    async fn lookup_homograph_asks() {
+        let server = MockDaum::start(vec![
+            ("/search.do", SEARCH_RAK),
+            ("/word/view.do", VIEW_HAK),
+            ("/word/view_supword.do", SUPWORD_HAK),
+        ])
+        .await;
+        let mut reply = Recorder::default();
+        lookup(&server.backend(), &mut reply, "樂").await.unwrap();
+        assert_eq!(reply.shown[1], "Which entry do you mean?");
+        assert_eq!(server.requests()[1], "/word/view.do?wordid=hjdic_0007001");
+    }
+
+    #[tokio::test]
+    async fn lookup_errors_reach_the_user() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_NO_RESULT)]).await;
+        let mut reply = Recorder::default();
+        let error = lookup(&server.backend(), &mut reply, "學")
+            .await
+            .unwrap_err();
+        assert_eq!(reply.shown, ["Searching for 學"]);
+        assert_eq!(error_message(&error, None), "No result");
+        assert_eq!(error_message(&error, Some("ko")), "검색 결과가 없습니다.");
+
+        let server = MockDaum::start(vec![
+            ("/search.do", SEARCH_HAK),
+            ("/word/view.do", VIEW_MALFORMED),
+            ("/word/view_supword.do", SUPWORD_HAK),
+        ])
+        .await;
+        let error = lookup(&server.backend(), &mut Recorder::default(), "學")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error_message(&error, None),
+            "Could not read the dictionary page (`.txt_read` is missing)."
+        );
+
+        let server = MockDaum::start(vec![]).await;
+        let error = lookup(&server.backend(), &mut Recorder::default(), "學")
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error_message(&error, Some("en-US")),
+            "The dictionary answered with `404 Not Found`. Please try again later."
+        );
+        assert_eq!(
+            error_message(&"boom".into(), Some("ko")),
+            "오류가 발생했습니다."
+        );
+    }
+
+    #[tokio::test]
+    async fn lookup_times_out() {
+        let server = MockDaum::start(vec![("/search.do", NO_RESPONSE)]).await;
+        let error = lookup(&server.backend(), &mut Recorder::default(), "學")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<LookupError>(),
+            Some(LookupError::Timeout)
+        ));
+        assert_eq!(
+            error_message(&error, None),
+            "The dictionary did not respond in time."
+        );
+    }
+
+    #[tokio::test]
+    async fn lookup_compound_offline() {
+        let mut reply = Recorder::default();
+        lookup(&UnihanBackend::bundled(), &mut reply, "學校")
+            .await
+            .unwrap();
+        assert!(
+            reply.shown[1].starts_with("# 學校\n**학교**\n"),
+            "{}",
+            reply.shown[1]
+        );
+    }
+
+    #[tokio::test]
+    async fn candidates_for_homographs() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_RAK)]).await;
+        let backend = server.backend();
+
+        let hits = candidates(&backend, "樂").await.unwrap();
+        let ids = hits.iter().map(|hit| hit.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, ["hjdic_0007001", "hjdic_0007002", "hjdic_0007003"]);
+    }
+
+    #[tokio::test]
+    async fn candidates_no_result() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_NO_RESULT)]).await;
+        let backend = server.backend();
+
+        assert!(matches!(
+            candidates(&backend, "學").await,
+            Err(LookupError::NotFound)
+        ));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn candidates_upstream_error() {
+        let server = MockDaum::start(vec![]).await;
+        let backend = server.backend();
+
+        assert!(matches!(
+            candidates(&backend, "學").await,
+            Err(LookupError::Http(reqwest::StatusCode::NOT_FOUND))
+        ));
+    }
+
+    #[tokio::test]
+    async fn fetch_malformed_entry() {
+        let server = MockDaum::start(vec![
+            ("/search.do", SEARCH_HAK),
+            ("/word/view.do", VIEW_MALFORMED),
+            ("/word/view_supword.do", SUPWORD_HAK),
+        ])
+        .await;
+        let backend = server.backend();
+
+        let hits = candidates(&backend, "學").await.unwrap();
+        assert!(matches!(
+            backend.fetch(&hits[0]).await,
+            Err(LookupError::Parse {
+                selector: ".txt_read"
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn breakdown_offline() {
+        let backend = UnihanBackend::bundled();
+        assert_eq!(
+            breakdown(&backend, "學校 가자").await,
+            [
+                CharacterGloss {
+                    character: "學".to_string(),
+                    reading: "학".to_string(),
+                    meaning: Some("learning, knowledge".to_string()),
+                },
+                CharacterGloss {
+                    character: "校".to_string(),
+                    reading: "교".to_string(),
+                    meaning: Some("school".to_string()),
+                },
+            ]
+        );
+    }
+}
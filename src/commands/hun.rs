@@ -0,0 +1,117 @@
+use super::{choose, non_empty, placeholder, show_entry};
+use crate::backend::{DictionaryBackend, LookupError, SearchHit};
+use crate::grade::Filter;
+use crate::text::is_syllable;
+use crate::{Context, Error};
+
+/// Find hanja by their meaning (훈) and optionally reading (음)
+///
+/// Results can be limited to a level such as `6급` or to the 중학교/고등학교
+/// 기초한자.
+#[poise::command(
+    prefix_command,
+    slash_command,
+    track_edits,
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn hun(
+    ctx: Context<'_>,
+    meaning: String,
+    reading: Option<String>,
+    level: Option<Filter>,
+) -> Result<(), Error> {
+    let (meaning, reading) = match reading {
+        Some(reading) => (meaning.trim(), Some(reading.trim().to_string())),
+        None => split_hun(&meaning),
+    };
+    if reading
+        .as_deref()
+        .is_some_and(|reading| !is_syllable(reading))
+    {
+        ctx.say("The reading must be a single hangul syllable, e.g. `배울 학`.")
+            .await?;
+        return Ok(());
+    }
+    let query = match &reading {
+        Some(reading) => format!("{meaning} {reading}"),
+        None => meaning.to_string(),
+    };
+    let result = placeholder(ctx, &query).await?;
+    let backend = &*ctx.data().backend;
+    let mut hits = characters(backend, meaning, reading.as_deref()).await?;
+    if let Some(filter) = level {
+        let grades = &ctx.data().grades;
+        hits.retain(|hit| grades.admits(filter, &hit.headword));
+    }
+    if hits.is_empty() {
+        return Err(LookupError::NotFound.into());
+    }
+    let prompt = format!("Hanja meaning {query}:");
+    let Some(hit) = choose(ctx, &result, &prompt, &hits).await? else {
+        return Ok(());
+    };
+    let entry = backend.fetch(&hit).await?;
+    show_entry(ctx, &result, &entry).await
+}
+
+/// Split `배울 학` into the meaning and a trailing one-syllable reading.
+fn split_hun(text: &str) -> (&str, Option<String>) {
+    let text = text.trim();
+    match text.rsplit_once(char::is_whitespace) {
+        Some((meaning, reading)) if is_syllable(reading) => {
+            (meaning.trim_end(), Some(reading.to_string()))
+        }
+        _ => (text, None),
+    }
+}
+
+/// Characters glossed as `meaning`.
+pub async fn characters(
+    backend: &dyn DictionaryBackend,
+    meaning: &str,
+    reading: Option<&str>,
+) -> Result<Vec<SearchHit>, LookupError> {
+    non_empty(backend.by_meaning(meaning, reading).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::mock_daum::MockDaum;
+
+    const SEARCH_HUN_BAEUL: &str = include_str!("../../fixtures/daum/search_hun_baeul.html");
+
+    #[test]
+    fn splits_reading() {
+        assert_eq!(split_hun(" 배울 학 "), ("배울", Some("학".to_string())));
+        assert_eq!(split_hun("배울"), ("배울", None));
+        assert_eq!(split_hun("익혀 배울"), ("익혀 배울", None));
+        assert_eq!(split_hun("물 수"), ("물", Some("수".to_string())));
+    }
+
+    #[tokio::test]
+    async fn characters_from_daum() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_HUN_BAEUL)]).await;
+        let backend = server.backend();
+
+        let headwords =
+            |hits: Vec<SearchHit>| hits.into_iter().map(|hit| hit.headword).collect::<Vec<_>>();
+        assert_eq!(
+            headwords(characters(&backend, "배울", None).await.unwrap()),
+            ["學", "斅", "講"]
+        );
+        assert_eq!(
+            headwords(characters(&backend, "배울", Some("학")).await.unwrap()),
+            ["學"]
+        );
+        assert!(matches!(
+            characters(&backend, "배울", Some("뷁")).await,
+            Err(LookupError::NotFound)
+        ));
+        assert_eq!(
+            server.requests()[1],
+            "/search.do?dic=hanja&q=%EB%B0%B0%EC%9A%B8+%ED%95%99"
+        );
+    }
+}
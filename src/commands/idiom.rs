@@ -0,0 +1,181 @@
+use poise::CreateReply;
+
+use super::hanja::breakdown;
+use super::{choose, navigate, non_empty, placeholder};
+use crate::backend::{plausible, DictionaryBackend, LookupError, SearchHit};
+use crate::entry::CharacterGloss;
+use crate::idiom::Idiom;
+use crate::text::{is_hangul, spoken};
+use crate::{render, Context, Error};
+
+/// Look up a four-character idiom (사자성어) in hanja or hangul
+///
+/// Without an idiom, shows a random one. Idioms missing from the table are
+/// looked up in the dictionary instead.
+#[poise::command(
+    prefix_command,
+    slash_command,
+    track_edits,
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn idiom(ctx: Context<'_>, idiom: Option<String>) -> Result<(), Error> {
+    let idioms = &ctx.data().idioms;
+    let backend = &*ctx.data().backend;
+    let (query, found) = match &idiom {
+        Some(query) => (query.trim(), idioms.find(query)),
+        None => ("a random idiom", idioms.random().into_iter().collect()),
+    };
+    if found.is_empty() && idiom.is_none() {
+        return Err(LookupError::NotFound.into());
+    }
+    let result = placeholder(ctx, query).await?;
+    if found.is_empty() {
+        let hits = candidates(backend, query).await?;
+        let Some(hit) = choose(ctx, &result, "Which word do you mean?", &hits).await? else {
+            return Ok(());
+        };
+        let entry = backend.fetch(&hit).await?;
+        let glosses = glosses(
+            backend,
+            &entry.headword,
+            &spoken(&entry.headword, &entry.reading),
+        )
+        .await;
+        result
+            .edit(
+                ctx,
+                CreateReply::default().content(render::idiom_entry(&entry, &glosses)),
+            )
+            .await?;
+        return Ok(());
+    }
+    let hits = found.iter().map(|idiom| hit(idiom)).collect::<Vec<_>>();
+    let Some(hit) = choose(ctx, &result, "Which idiom do you mean?", &hits).await? else {
+        return Ok(());
+    };
+    let Some(mut idiom) = idioms.get(&hit.id) else {
+        return Err(LookupError::NotFound.into());
+    };
+
+    loop {
+        let glosses = glosses(backend, &idiom.idiom, &idiom.reading).await;
+        let related = idiom
+            .related
+            .iter()
+            .filter_map(|related| idioms.get(related))
+            .collect::<Vec<_>>();
+        let mut actions = related
+            .iter()
+            .map(|related| format!("{} {}", related.idiom, related.reading))
+            .collect::<Vec<_>>();
+        actions.push("🎲".to_string());
+        let content = render::idiom(idiom, &glosses, &related);
+        let Some(i) = navigate(ctx, &result, 0, 1, &actions, |_| {
+            std::future::ready(Ok(CreateReply::default().content(content.clone())))
+        })
+        .await?
+        else {
+            return Ok(());
+        };
+        // The last button asks for another random idiom.
+        idiom = match related.get(i) {
+            Some(related) => related,
+            None => idioms.random().unwrap_or(idiom),
+        };
+    }
+}
+
+/// `idiom` as a candidate for [`choose`], identified by its hanja.
+fn hit(idiom: &Idiom) -> SearchHit {
+    SearchHit {
+        id: idiom.idiom.clone(),
+        headword: idiom.idiom.clone(),
+        reading: Some(idiom.reading.clone()),
+        gloss: Some(idiom.meaning.clone()),
+        source: "idioms".to_string(),
+    }
+}
+
+/// Dictionary words for a query missing from the idiom table: words read as
+/// `query` if it is hangul, or else those written as it.
+async fn candidates(
+    backend: &dyn DictionaryBackend,
+    query: &str,
+) -> Result<Vec<SearchHit>, LookupError> {
+    non_empty(if query.chars().all(is_hangul) {
+        backend.by_reading(query).await?
+    } else {
+        plausible(query, backend.search(query).await?)
+    })
+}
+
+/// Reading and meaning of each character of `idiom`, taking the syllable of
+/// its `reading` for characters the dictionary does not know.
+pub async fn glosses(
+    backend: &dyn DictionaryBackend,
+    idiom: &str,
+    reading: &str,
+) -> Vec<CharacterGloss> {
+    let found = breakdown(backend, idiom).await;
+    idiom
+        .chars()
+        .zip(reading.chars())
+        .map(|(character, syllable)| {
+            let character = character.to_string();
+            found
+                .iter()
+                .find(|gloss| gloss.character == character)
+                .cloned()
+                .unwrap_or_else(|| CharacterGloss {
+                    character,
+                    reading: syllable.to_string(),
+                    meaning: None,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::unihan::UnihanBackend;
+    use crate::idiom::Idioms;
+    use crate::mock_daum::MockDaum;
+
+    const SEARCH_DAEHANMINGUK: &str = include_str!("../../fixtures/daum/search_daehanminguk.html");
+
+    #[tokio::test]
+    async fn glosses_offline() {
+        let backend = UnihanBackend::bundled();
+        let idioms = Idioms::bundled();
+        let idiom = idioms.get("敎學相長").unwrap();
+        let glosses = glosses(&backend, &idiom.idiom, &idiom.reading).await;
+        let readings = glosses
+            .iter()
+            .map(|gloss| (gloss.character.as_str(), gloss.reading.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            readings,
+            [("敎", "교"), ("學", "학"), ("相", "상"), ("長", "장")]
+        );
+        assert_eq!(glosses[1].meaning.as_deref(), Some("learning, knowledge"));
+        // 相 is missing from the bundled Unihan subset.
+        assert_eq!(glosses[2].meaning, None);
+    }
+
+    #[tokio::test]
+    async fn candidates_beyond_the_table() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_DAEHANMINGUK)]).await;
+        let backend = server.backend();
+        let hits = candidates(&backend, "대한민국").await.unwrap();
+        assert_eq!(hits[0].headword, "大韓民國");
+        let hits = candidates(&backend, "大韓民國").await.unwrap();
+        assert_eq!(hits[0].reading.as_deref(), Some("대한민국"));
+
+        let backend = UnihanBackend::bundled();
+        assert!(matches!(
+            candidates(&backend, "塞翁之馬").await,
+            Err(LookupError::NotFound)
+        ));
+    }
+}
@@ -0,0 +1,485 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use futures::future::join_all;
+use futures::StreamExt;
+use poise::serenity_prelude as serenity;
+use rand::seq::SliceRandom;
+
+use crate::backend::{DictionaryBackend, LookupError};
+use crate::entry::HanjaEntry;
+use crate::grade::Filter;
+use crate::render::truncate;
+use crate::{Context, Error};
+
+/// Questions asked when `quiz start` is not given a number.
+const DEFAULT_ROUNDS: u32 = 10;
+const MAX_ROUNDS: u32 = 50;
+/// Readings offered as buttons, the right one included.
+const CHOICES: usize = 4;
+/// Time before each hint and after the last one.
+const PHASE: Duration = Duration::from_secs(10);
+/// Users listed by `quiz top`.
+const LEADERBOARD: usize = 10;
+/// Points for an answer without hints; each hint shown costs one.
+const MAX_POINTS: u32 = 3;
+
+/// Quiz running in a channel.
+#[derive(Debug)]
+pub struct Session {
+    /// User who started the quiz and may stop it.
+    pub host: serenity::UserId,
+    pub scores: HashMap<serenity::UserId, u32>,
+    /// Set by `quiz stop` to end the quiz after the current question.
+    pub stopped: bool,
+}
+
+/// Character to read, with the readings offered as buttons.
+#[derive(Debug)]
+struct Question {
+    entry: HanjaEntry,
+    choices: Vec<String>,
+    /// Index of the reading of `entry` in `choices`.
+    answer: usize,
+}
+
+/// Guess the 훈음 of hanja
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands("start", "stop", "score", "top"),
+    subcommand_required
+)]
+pub async fn quiz(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// Start a quiz in this channel, optionally limited to a level such as `6급` or `중학교`
+#[poise::command(prefix_command, slash_command, required_permissions = "SEND_MESSAGES")]
+pub async fn start(
+    ctx: Context<'_>,
+    level: Option<Filter>,
+    rounds: Option<u32>,
+) -> Result<(), Error> {
+    let rounds = rounds.unwrap_or(DEFAULT_ROUNDS).clamp(1, MAX_ROUNDS);
+    let pool = ctx.data().grades.characters(level);
+    if pool.len() < CHOICES {
+        ctx.say("There are not enough characters at that level for a quiz.")
+            .await?;
+        return Ok(());
+    }
+    let channel_id = ctx.channel_id();
+    let started = {
+        let mut quizzes = ctx.data().quizzes.lock().unwrap();
+        let vacant = !quizzes.contains_key(&channel_id);
+        if vacant {
+            quizzes.insert(
+                channel_id,
+                Session {
+                    host: ctx.author().id,
+                    scores: HashMap::new(),
+                    stopped: false,
+                },
+            );
+        }
+        vacant
+    };
+    if !started {
+        ctx.say("A quiz is already running in this channel.")
+            .await?;
+        return Ok(());
+    }
+    let announced = ctx
+        .say(format!(
+            "Starting a quiz of {rounds} questions! Answer with the 훈음 of each character, \
+             e.g. `배울 학`, in the chat or with the buttons."
+        ))
+        .await;
+    if let Err(e) = announced {
+        // Free the channel again, or it would report a quiz running forever.
+        ctx.data().quizzes.lock().unwrap().remove(&channel_id);
+        return Err(e.into());
+    }
+
+    let result = run(ctx, &pool, rounds).await;
+    let session = ctx.data().quizzes.lock().unwrap().remove(&channel_id);
+    if let Some(session) = session {
+        if let Err(e) = ctx
+            .data()
+            .store
+            .record_quiz(ctx.guild_id(), channel_id, &session.scores)
+        {
+            tracing::error!("Failed to save quiz scores: {e}");
+        }
+        channel_id
+            .say(
+                ctx,
+                format!("## Final scores\n{}", scoreboard(&session.scores)),
+            )
+            .await?;
+    }
+    result
+}
+
+/// Stop the quiz in this channel after the current question
+#[poise::command(prefix_command, slash_command)]
+pub async fn stop(ctx: Context<'_>) -> Result<(), Error> {
+    let content = match ctx
+        .data()
+        .quizzes
+        .lock()
+        .unwrap()
+        .get_mut(&ctx.channel_id())
+    {
+        None => "No quiz is running in this channel.".to_string(),
+        Some(session) if session.host != ctx.author().id => {
+            format!("Only <@{}> can stop this quiz.", session.host)
+        }
+        Some(session) => {
+            session.stopped = true;
+            "The quiz will stop after this question.".to_string()
+        }
+    };
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Show the scores of the quiz in this channel and your own over all quizzes
+#[poise::command(prefix_command, slash_command)]
+pub async fn score(ctx: Context<'_>) -> Result<(), Error> {
+    let session = match ctx.data().quizzes.lock().unwrap().get(&ctx.channel_id()) {
+        Some(session) => scoreboard(&session.scores),
+        None => "No quiz is running in this channel.".to_string(),
+    };
+    let profile = ctx.data().store.profile(ctx.author().id)?;
+    let content = format!(
+        "{session}\n-# You have {} points from {} quizzes.",
+        profile.quiz_points, profile.quizzes
+    );
+    ctx.send(
+        poise::CreateReply::default()
+            .content(content)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Show who has the most quiz points in this server
+#[poise::command(prefix_command, slash_command, guild_only)]
+pub async fn top(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let leaders = ctx.data().store.leaderboard(guild_id, LEADERBOARD)?;
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format!(
+                "## Top scores\n{}",
+                scoreboard(&leaders.into_iter().collect())
+            ))
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Ask up to `rounds` questions about characters of `pool`, adding the
+/// points of each to the channel's session.
+async fn run(ctx: Context<'_>, pool: &[char], rounds: u32) -> Result<(), Error> {
+    let backend = &*ctx.data().backend;
+    for round in 1..=rounds {
+        if is_stopped(ctx) {
+            break;
+        }
+        let question = question(backend, pool).await?;
+        if let Some((user, points)) = ask(ctx, &question, round, rounds).await? {
+            let mut quizzes = ctx.data().quizzes.lock().unwrap();
+            if let Some(session) = quizzes.get_mut(&ctx.channel_id()) {
+                *session.scores.entry(user).or_default() += points;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_stopped(ctx: Context<'_>) -> bool {
+    ctx.data()
+        .quizzes
+        .lock()
+        .unwrap()
+        .get(&ctx.channel_id())
+        .is_none_or(|session| session.stopped)
+}
+
+/// Something said or pressed while a question is open.
+enum Answer {
+    Message(Box<serenity::Message>),
+    Press(Box<serenity::ComponentInteraction>),
+}
+
+/// Post `question` and wait for the first right answer, showing a hint
+/// after each [`PHASE`] without one.
+///
+/// Returns who answered and the points earned, or `None` if time ran out.
+async fn ask(
+    ctx: Context<'_>,
+    question: &Question,
+    round: u32,
+    rounds: u32,
+) -> Result<Option<(serenity::UserId, u32)>, Error> {
+    let hints = hints(&question.entry);
+    let buttons = question
+        .choices
+        .iter()
+        .enumerate()
+        .map(|(i, choice)| {
+            serenity::CreateButton::new(format!("{}:{round}:choice:{i}", ctx.id()))
+                .label(truncate(choice, 80))
+                .style(serenity::ButtonStyle::Secondary)
+        })
+        .collect::<Vec<_>>();
+    let mut message = ctx
+        .channel_id()
+        .send_message(
+            ctx,
+            serenity::CreateMessage::new()
+                .content(prompt(question, round, rounds, &hints[..0]))
+                .components(vec![serenity::CreateActionRow::Buttons(buttons)]),
+        )
+        .await?;
+
+    // Users who pressed a wrong button and may not answer again.
+    let mut out = HashSet::new();
+    let mut winner = None;
+    'phases: for shown in 0..=hints.len() {
+        if shown > 0 {
+            message
+                .edit(
+                    ctx,
+                    serenity::EditMessage::new().content(prompt(
+                        question,
+                        round,
+                        rounds,
+                        &hints[..shown],
+                    )),
+                )
+                .await?;
+        }
+        let messages = serenity::MessageCollector::new(ctx)
+            .channel_id(ctx.channel_id())
+            .timeout(PHASE)
+            .stream()
+            .map(|message| Answer::Message(Box::new(message)));
+        let presses = serenity::ComponentInteractionCollector::new(ctx)
+            .message_id(message.id)
+            .timeout(PHASE)
+            .stream()
+            .map(|press| Answer::Press(Box::new(press)));
+        let mut answers = futures::stream::select(messages, presses);
+        while let Some(answer) = answers.next().await {
+            let points = MAX_POINTS.saturating_sub(shown as u32).max(1);
+            match answer {
+                Answer::Message(answer) => {
+                    if !answer.author.bot
+                        && !out.contains(&answer.author.id)
+                        && is_correct(&question.entry.reading, &answer.content)
+                    {
+                        winner = Some((answer.author.id, points));
+                        break 'phases;
+                    }
+                }
+                Answer::Press(press) => {
+                    let right = press.data.custom_id.rsplit(':').next()
+                        == Some(&question.answer.to_string());
+                    if right && !out.contains(&press.user.id) {
+                        press
+                            .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+                            .await?;
+                        winner = Some((press.user.id, points));
+                        break 'phases;
+                    }
+                    let reply = if right {
+                        "You already answered this question."
+                    } else {
+                        out.insert(press.user.id);
+                        "Not quite! You can try again on the next question."
+                    };
+                    press
+                        .create_response(
+                            ctx,
+                            serenity::CreateInteractionResponse::Message(
+                                serenity::CreateInteractionResponseMessage::new()
+                                    .content(reply)
+                                    .ephemeral(true),
+                            ),
+                        )
+                        .await?;
+                }
+            }
+        }
+    }
+
+    let outcome = match winner {
+        Some((user, points)) => format!("✅ <@{user}> got it (+{points})"),
+        None => "⌛ Time's up".to_string(),
+    };
+    message
+        .edit(
+            ctx,
+            serenity::EditMessage::new()
+                .content(format!(
+                    "**{round}/{rounds}** {outcome}\n# {}\n**{}**",
+                    question.entry.headword, question.entry.reading
+                ))
+                .components(vec![]),
+        )
+        .await?;
+    Ok(winner)
+}
+
+/// Pick a character of `pool` to ask about, and others whose readings are
+/// offered alongside its own.
+async fn question(backend: &dyn DictionaryBackend, pool: &[char]) -> Result<Question, Error> {
+    let picked = pool
+        .choose_multiple(&mut rand::thread_rng(), CHOICES)
+        .map(char::to_string)
+        .collect::<Vec<_>>();
+    let lookups = picked.iter().map(|character| backend.lookup(character));
+    let mut entries = join_all(lookups).await.into_iter().zip(&picked).filter_map(
+        |(entry, character)| match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                tracing::warn!("Failed to look up {character} for a quiz: {e}");
+                None
+            }
+        },
+    );
+    let entry = entries.next().ok_or(LookupError::NotFound)?;
+    let mut choices = vec![choice(&entry.reading)];
+    for other in entries {
+        let other = choice(&other.reading);
+        if !choices.contains(&other) {
+            choices.push(other);
+        }
+    }
+    choices.shuffle(&mut rand::thread_rng());
+    let answer = choices
+        .iter()
+        .position(|choice| *choice == self::choice(&entry.reading))
+        .unwrap_or_default();
+    Ok(Question {
+        entry,
+        choices,
+        answer,
+    })
+}
+
+/// First of the readings of an entry, e.g. `노래 악` of `노래 악, 풍류 악`.
+fn choice(reading: &str) -> String {
+    reading
+        .split(',')
+        .next()
+        .unwrap_or(reading)
+        .trim()
+        .to_string()
+}
+
+/// Whether `answer` is one of the readings of an entry, ignoring spaces.
+fn is_correct(reading: &str, answer: &str) -> bool {
+    let squeeze = |text: &str| text.split_whitespace().collect::<String>();
+    let answer = squeeze(answer);
+    !answer.is_empty() && reading.split(',').any(|reading| squeeze(reading) == answer)
+}
+
+/// Hints in the order they are shown: the radical, then the stroke count.
+fn hints(entry: &HanjaEntry) -> Vec<String> {
+    let mut hints = Vec::new();
+    if let Some(radical) = &entry.radical {
+        hints.push(format!("부수 {}", radical.character));
+    }
+    if let Some(strokes) = entry.strokes {
+        hints.push(format!("총 {strokes}획"));
+    }
+    hints
+}
+
+fn prompt(question: &Question, round: u32, rounds: u32, hints: &[String]) -> String {
+    let mut lines = vec![
+        format!("**{round}/{rounds}** What is the 훈음 of this character?"),
+        format!("# {}", question.entry.headword),
+    ];
+    lines.extend(hints.iter().map(|hint| format!("-# 💡 {hint}")));
+    lines.join("\n")
+}
+
+/// Scores from the highest, one line per user.
+fn scoreboard(scores: &HashMap<serenity::UserId, u32>) -> String {
+    let mut scores = scores.iter().collect::<Vec<_>>();
+    scores.sort_by(|(a, a_points), (b, b_points)| b_points.cmp(a_points).then(a.cmp(b)));
+    if scores.is_empty() {
+        return "-# Nobody has scored yet.".to_string();
+    }
+    scores
+        .iter()
+        .enumerate()
+        .map(|(i, (user, points))| format!("{}. <@{user}> {points}", i + 1))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::unihan::UnihanBackend;
+    use crate::entry::Radical;
+
+    #[test]
+    fn answers() {
+        assert!(is_correct("배울 학", "배울 학"));
+        assert!(is_correct("배울 학", " 배울학 "));
+        assert!(is_correct("노래 악, 풍류 악", "풍류 악"));
+        assert!(!is_correct("배울 학", "학"));
+        assert!(!is_correct("배울 학", ""));
+        assert_eq!(choice("노래 악, 풍류 악"), "노래 악");
+    }
+
+    #[test]
+    fn hints_in_order() {
+        let mut entry = HanjaEntry::new("學".to_string(), "배울 학".to_string());
+        assert!(hints(&entry).is_empty());
+        entry.radical = Some(Radical {
+            character: "子".to_string(),
+            remaining_strokes: Some(13),
+        });
+        entry.strokes = Some(16);
+        assert_eq!(hints(&entry), ["부수 子", "총 16획"]);
+    }
+
+    #[test]
+    fn scoreboard_from_highest() {
+        let scores = HashMap::from([(serenity::UserId::new(1), 2), (serenity::UserId::new(2), 5)]);
+        assert_eq!(scoreboard(&scores), "1. <@2> 5\n2. <@1> 2");
+        assert_eq!(scoreboard(&HashMap::new()), "-# Nobody has scored yet.");
+    }
+
+    #[tokio::test]
+    async fn question_offline() {
+        let backend = UnihanBackend::bundled();
+        let pool = ['學', '校', '水', '火'];
+        let question = question(&backend, &pool).await.unwrap();
+        assert!(pool
+            .iter()
+            .any(|character| question.entry.headword == character.to_string()));
+        assert_eq!(question.choices.len(), CHOICES);
+        assert!(is_correct(
+            &question.entry.reading,
+            &question.choices[question.answer]
+        ));
+    }
+}
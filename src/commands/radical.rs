@@ -0,0 +1,88 @@
+use poise::CreateReply;
+
+use super::{navigate, placeholder};
+use crate::{radical, render, Context, Error};
+
+/// Number of Kangxi radicals.
+const RADICALS: u8 = 214;
+
+/// Browse characters by radical (부수) and remaining strokes
+///
+/// The index comes from the Unihan data of the offline dictionary, so it only
+/// lists the characters of the bundled subset unless `UNIHAN_PATH` points at
+/// a full Unihan export.
+#[poise::command(
+    prefix_command,
+    slash_command,
+    track_edits,
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn radical(ctx: Context<'_>, radical: String) -> Result<(), Error> {
+    let Some(number) = parse(&radical) else {
+        ctx.say("Please give a radical such as `子`, or its number from 1 to 214.")
+            .await?;
+        return Ok(());
+    };
+    let result = placeholder(ctx, radical.trim()).await?;
+    let backend = &*ctx.data().backend;
+    let mut number = number;
+    loop {
+        let hits = backend.by_radical(number).await?;
+        let pages = render::radical_index(number, &hits);
+        // Buttons for the radicals before and after this one.
+        let neighbours = [number - 1, number + 1]
+            .into_iter()
+            .filter(|neighbour| (1..=RADICALS).contains(neighbour))
+            .collect::<Vec<_>>();
+        let actions = neighbours
+            .iter()
+            .map(|&neighbour| {
+                let character = radical::character(neighbour).unwrap_or('?');
+                if neighbour < number {
+                    format!("◀ {character} {neighbour}")
+                } else {
+                    format!("{character} {neighbour} ▶")
+                }
+            })
+            .collect::<Vec<_>>();
+        let Some(i) = navigate(ctx, &result, 0, pages.len(), &actions, |page| {
+            std::future::ready(Ok(CreateReply::default().content(pages[page].clone())))
+        })
+        .await?
+        else {
+            return Ok(());
+        };
+        number = neighbours[i];
+    }
+}
+
+/// Kangxi number of a radical given as itself or its number.
+fn parse(text: &str) -> Option<u8> {
+    let text = text.trim();
+    match text.parse::<u8>() {
+        Ok(number) => (1..=RADICALS).contains(&number).then_some(number),
+        Err(_) => {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(character), None) => radical::number(character),
+                _ => None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_radicals() {
+        assert_eq!(parse("子"), Some(39));
+        assert_eq!(parse(" 39 "), Some(39));
+        assert_eq!(parse("214"), Some(214));
+        assert_eq!(parse("0"), None);
+        assert_eq!(parse("215"), None);
+        assert_eq!(parse("學"), None);
+        assert_eq!(parse("子子"), None);
+    }
+}
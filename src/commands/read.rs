@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+
+use super::hanja::breakdown;
+use super::PREFIX;
+use crate::backend::DictionaryBackend;
+use crate::render::truncate;
+use crate::text::{self, initial_sound, is_hanja, spoken};
+use crate::{render, Context, Data, Error};
+
+/// Most distinct characters glossed for one message.
+const MAX_CHARACTERS: usize = 20;
+/// Longest word looked up as a whole when reading a run of hanja.
+const MAX_WORD: usize = 4;
+/// Most hanja read from one text; those after them are left as written.
+const MAX_HANJA: usize = 40;
+/// Shortest time between two passive readings in one channel.
+const PASSIVE_COOLDOWN: Duration = Duration::from_secs(10);
+
+/// Write the hanja in a text in hangul
+#[poise::command(
+    prefix_command,
+    slash_command,
+    track_edits,
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn read(ctx: Context<'_>, #[rest] text: String) -> Result<(), Error> {
+    if !text.chars().any(is_hanja) {
+        ctx.say("There is no hanja in this text.").await?;
+        return Ok(());
+    }
+    ctx.defer().await?;
+    let content = transliterate(&*ctx.data().backend, &text).await;
+    ctx.send(
+        CreateReply::default()
+            .content(truncate(&content, 2000))
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Reply with the readings of the hanja in a message
+#[poise::command(
+    context_menu_command = "Read hanja",
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn read_hanja(ctx: Context<'_>, message: serenity::Message) -> Result<(), Error> {
+    if !message.content.chars().any(is_hanja) {
+        ctx.send(
+            CreateReply::default()
+                .content("There is no hanja in this message.")
+                .ephemeral(true),
+        )
+        .await?;
+        return Ok(());
+    }
+    ctx.defer().await?;
+    let content = annotate(&*ctx.data().backend, &message.content).await;
+    ctx.send(
+        CreateReply::default()
+            .content(content)
+            .allowed_mentions(serenity::CreateAllowedMentions::new()),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Turn passive reading of hanja in this channel's messages on or off
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    required_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn passive(ctx: Context<'_>, enabled: bool) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    ctx.data()
+        .store
+        .set_passive(guild_id, ctx.channel_id(), enabled)?;
+    {
+        let mut channels = ctx.data().passive_channels.lock().unwrap();
+        if enabled {
+            channels.insert(ctx.channel_id());
+        } else {
+            channels.remove(&ctx.channel_id());
+        }
+    }
+    ctx.say(if enabled {
+        "I will now read the hanja in messages here."
+    } else {
+        "I will no longer read the hanja in messages here."
+    })
+    .await?;
+    Ok(())
+}
+
+/// Annotate `message` if its channel is in passive mode.
+pub async fn on_message(
+    ctx: &serenity::Context,
+    data: &Data,
+    message: &serenity::Message,
+) -> Result<(), Error> {
+    if message.author.bot
+        // Commands show their own results.
+        || message.content.starts_with(PREFIX)
+        || !message.content.chars().any(is_hanja)
+        || !data
+            .passive_channels
+            .lock()
+            .unwrap()
+            .contains(&message.channel_id)
+        || !data
+            .passive_cooldown
+            .lock()
+            .unwrap()
+            .allow(message.channel_id, Instant::now())
+    {
+        return Ok(());
+    }
+    let content = annotate(&*data.backend, &message.content).await;
+    message
+        .channel_id
+        .send_message(
+            ctx,
+            serenity::CreateMessage::new()
+                .content(content)
+                .reference_message(message)
+                .allowed_mentions(serenity::CreateAllowedMentions::new()),
+        )
+        .await?;
+    Ok(())
+}
+
+/// When each passive channel was last read.
+#[derive(Default)]
+pub struct Cooldown {
+    last: HashMap<serenity::ChannelId, Instant>,
+}
+
+impl Cooldown {
+    /// Whether `channel` may be read at `now`, given [`PASSIVE_COOLDOWN`],
+    /// counting it as read if so.
+    pub fn allow(&mut self, channel: serenity::ChannelId, now: Instant) -> bool {
+        match self.last.get(&channel) {
+            Some(&last) if now.saturating_duration_since(last) < PASSIVE_COOLDOWN => false,
+            _ => {
+                self.last.insert(channel, now);
+                true
+            }
+        }
+    }
+}
+
+/// `text` split after its first [`MAX_HANJA`] hanja.
+fn capped(text: &str) -> (&str, &str) {
+    let end = text
+        .char_indices()
+        .filter(|&(_, c)| is_hanja(c))
+        .nth(MAX_HANJA)
+        .map_or(text.len(), |(i, _)| i);
+    text.split_at(end)
+}
+
+/// `text` with readings of its first [`MAX_HANJA`] hanja and glosses of the
+/// first [`MAX_CHARACTERS`] distinct ones, cut after the last hanja read.
+pub async fn annotate(backend: &dyn DictionaryBackend, text: &str) -> String {
+    let text = match capped(text) {
+        (text, "") => text.to_string(),
+        (text, _) => format!("{text}…"),
+    };
+    let text = text.as_str();
+    let mut characters = Vec::new();
+    for c in text.chars().filter(|&c| is_hanja(c)) {
+        if !characters.contains(&c) && characters.len() < MAX_CHARACTERS {
+            characters.push(c);
+        }
+    }
+    let readings = readings(backend, text).await;
+    let glosses = breakdown(backend, &characters.into_iter().collect::<String>()).await;
+    render::annotation(text, &readings, &glosses)
+}
+
+/// `text` with every run of hanja replaced by its reading, up to
+/// [`MAX_HANJA`] hanja.
+pub async fn transliterate(backend: &dyn DictionaryBackend, text: &str) -> String {
+    let (text, rest) = capped(text);
+    let mut readings = readings(backend, text).await.into_iter();
+    let mut transliterated = text::runs(text, is_hanja)
+        .into_iter()
+        .map(|(hanja, run)| {
+            if hanja {
+                readings.next().unwrap_or_default()
+            } else {
+                run.to_string()
+            }
+        })
+        .collect::<String>();
+    transliterated.push_str(rest);
+    transliterated
+}
+
+/// Hangul reading of each run of hanja in `text`, in order.
+///
+/// Runs are split into the longest dictionary words, whose readings settle
+/// characters with several readings, and the rest is read character by
+/// character. The initial sound law applies at the start of each run;
+/// characters that cannot be read become `?`.
+pub async fn readings(backend: &dyn DictionaryBackend, text: &str) -> Vec<String> {
+    let mut readings = Vec::new();
+    for (hanja, run) in text::runs(text, is_hanja) {
+        if !hanja {
+            continue;
+        }
+        let chars = run.chars().collect::<Vec<_>>();
+        let mut reading = String::new();
+        let mut start = 0;
+        while start < chars.len() {
+            let mut word = None;
+            for len in (2..=MAX_WORD.min(chars.len() - start)).rev() {
+                let headword = chars[start..start + len].iter().collect::<String>();
+                if let Some(found) = word_reading(backend, &headword).await {
+                    word = Some((len, found));
+                    break;
+                }
+            }
+            let (len, found) = match word {
+                Some(word) => word,
+                None => {
+                    let headword = chars[start].to_string();
+                    (
+                        1,
+                        word_reading(backend, &headword)
+                            .await
+                            .unwrap_or("?".to_string()),
+                    )
+                }
+            };
+            reading.push_str(&found);
+            start += len;
+        }
+        readings.push(initial_sound(&reading));
+    }
+    readings
+}
+
+/// Reading of the entry whose headword is exactly `headword`.
+async fn word_reading(backend: &dyn DictionaryBackend, headword: &str) -> Option<String> {
+    let hits = match backend.search(headword).await {
+        Ok(hits) => hits,
+        Err(e) => {
+            tracing::warn!("Failed to look up {headword} for its reading: {e}");
+            return None;
+        }
+    };
+    let hit = hits.into_iter().find(|hit| hit.headword == headword)?;
+    Some(spoken(headword, hit.reading.as_deref()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::backend::unihan::UnihanBackend;
+    use crate::mock_daum::MockDaum;
+
+    const SEARCH_RAK: &str = include_str!("../../fixtures/daum/search_rak.html");
+
+    #[tokio::test]
+    async fn transliterate_offline() {
+        let backend = UnihanBackend::bundled();
+        assert_eq!(transliterate(&backend, "李 선생").await, "이 선생");
+        assert_eq!(transliterate(&backend, "女子와 男女").await, "여자와 남녀");
+        assert_eq!(transliterate(&backend, "老人 鶴").await, "노인 ?");
+    }
+
+    #[tokio::test]
+    async fn transliterate_by_word() {
+        let server = MockDaum::start(vec![("/search.do", SEARCH_RAK)]).await;
+        let backend = server.backend();
+        // 樂 is read 락 alone but 낙 as the first syllable of 樂園.
+        assert_eq!(transliterate(&backend, "樂園에서").await, "낙원에서");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn long_texts_are_capped() {
+        let backend = UnihanBackend::bundled();
+        let text = "女".repeat(MAX_HANJA + 2);
+        assert_eq!(
+            transliterate(&backend, &format!("{text}子")).await,
+            format!("여{}女女子", "녀".repeat(MAX_HANJA - 1))
+        );
+        let annotated = annotate(&backend, &text).await;
+        assert!(annotated.starts_with(&format!("{}(", "女".repeat(MAX_HANJA))));
+        assert!(annotated.lines().next().unwrap().ends_with(")…"));
+    }
+
+    #[test]
+    fn passive_cooldown() {
+        let mut cooldown = Cooldown::default();
+        let channel = serenity::ChannelId::new(1);
+        let now = Instant::now();
+        assert!(cooldown.allow(channel, now));
+        assert!(!cooldown.allow(channel, now + Duration::from_secs(1)));
+        assert!(cooldown.allow(serenity::ChannelId::new(2), now));
+        assert!(cooldown.allow(channel, now + PASSIVE_COOLDOWN));
+    }
+
+    #[tokio::test]
+    async fn annotate_offline() {
+        let backend = UnihanBackend::bundled();
+        let annotated = annotate(&backend, "大韓民國 만세, 大學!").await;
+        let mut lines = annotated.lines();
+        assert_eq!(lines.next(), Some("大韓民國(대한민국) 만세, 大學(대학)!"));
+        assert_eq!(
+            lines
+                .map(|line| line.split(' ').nth(1).unwrap())
+                .collect::<Vec<_>>(),
+            ["**大**", "**韓**", "**民**", "**國**", "**學**"]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use poise::serenity_prelude as serenity;
+use poise::{CreateReply, ReplyHandle};
+
+use crate::review::{format_delay, Rating, DAY_SECS};
+use crate::store::{self, Word};
+use crate::{Context, Error};
+
+/// Most cards shown in one review.
+const SESSION_CARDS: usize = 20;
+
+/// Review the due hanja of your wordbook, here or by DM
+#[poise::command(prefix_command, slash_command)]
+pub async fn review(ctx: Context<'_>, dm: Option<bool>) -> Result<(), Error> {
+    let store = &ctx.data().store;
+    let user_id = ctx.author().id;
+    let now = store::now();
+    let cards = store.due_cards(user_id, now, SESSION_CARDS)?;
+    if cards.is_empty() {
+        let content = match store.next_due(user_id)? {
+            Some(due) => format!(
+                "Nothing to review right now. The next card is due in {}.",
+                format_delay(due.saturating_sub(now))
+            ),
+            None => "Your wordbook is empty. Save hanja with the 💾 Save button on search results."
+                .to_string(),
+        };
+        ctx.send(CreateReply::default().content(content).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+    let mut surface = surface(ctx, dm.unwrap_or(false)).await?;
+
+    let reveal = format!("{}:reveal", ctx.id());
+    let ratings = Rating::ALL.map(|rating| format!("{}:rate:{rating}", ctx.id()));
+    let mut reviewed = 0;
+    for (i, due) in cards.iter().enumerate() {
+        let progress = format!("-# {}/{}", i + 1, cards.len());
+        let show = serenity::CreateButton::new(&reveal)
+            .label("Show answer")
+            .style(serenity::ButtonStyle::Primary);
+        surface
+            .show(ctx, front(&progress, &due.word), vec![show])
+            .await?;
+        if press(ctx, std::slice::from_ref(&reveal)).await?.is_none() {
+            break;
+        }
+
+        let buttons = Rating::ALL
+            .iter()
+            .zip(&ratings)
+            .map(|(&rating, id)| {
+                let delay = format_delay(due.card.review(rating).delay_secs());
+                serenity::CreateButton::new(id)
+                    .label(format!("{rating} · {delay}"))
+                    .style(match rating {
+                        Rating::Again => serenity::ButtonStyle::Danger,
+                        Rating::Hard => serenity::ButtonStyle::Secondary,
+                        Rating::Good => serenity::ButtonStyle::Success,
+                        Rating::Easy => serenity::ButtonStyle::Primary,
+                    })
+            })
+            .collect();
+        surface
+            .show(ctx, back(&progress, &due.word), buttons)
+            .await?;
+        let Some(rating) = press(ctx, &ratings).await? else {
+            break;
+        };
+        let card = due.card.review(Rating::ALL[rating]);
+        store.schedule(user_id, &due.word.headword, card, store::now())?;
+        reviewed += 1;
+    }
+
+    let summary = if reviewed == 0 {
+        "Review ended without any answers.".to_string()
+    } else {
+        let streak = store.mark_reviewed(user_id, store::now() / DAY_SECS)?;
+        format!(
+            "Reviewed {reviewed} of {} cards. 🔥 {streak}-day streak",
+            cards.len()
+        )
+    };
+    surface.show(ctx, summary, vec![]).await
+}
+
+/// Message the cards of a review are shown on.
+enum Surface<'a> {
+    /// Ephemeral reply to the command.
+    Reply(ReplyHandle<'a>),
+    Direct(Box<serenity::Message>),
+}
+
+impl Surface<'_> {
+    async fn show(
+        &mut self,
+        ctx: Context<'_>,
+        content: String,
+        buttons: Vec<serenity::CreateButton>,
+    ) -> Result<(), Error> {
+        let components = if buttons.is_empty() {
+            vec![]
+        } else {
+            vec![serenity::CreateActionRow::Buttons(buttons)]
+        };
+        match self {
+            Self::Reply(handle) => {
+                handle
+                    .edit(
+                        ctx,
+                        CreateReply::default()
+                            .content(content)
+                            .components(components),
+                    )
+                    .await?
+            }
+            Self::Direct(message) => {
+                message
+                    .edit(
+                        ctx,
+                        serenity::EditMessage::new()
+                            .content(content)
+                            .components(components),
+                    )
+                    .await?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Open a DM with the invoking user if asked to and possible, or else an
+/// ephemeral reply.
+async fn surface(ctx: Context<'_>, dm: bool) -> Result<Surface<'_>, Error> {
+    let starting = "Starting your review…";
+    if dm {
+        match ctx
+            .author()
+            .direct_message(ctx, serenity::CreateMessage::new().content(starting))
+            .await
+        {
+            Ok(message) => {
+                ctx.send(
+                    CreateReply::default()
+                        .content("Your review is waiting in your DMs.")
+                        .ephemeral(true),
+                )
+                .await?;
+                return Ok(Surface::Direct(Box::new(message)));
+            }
+            Err(e) => tracing::warn!("Failed to DM a review: {e}"),
+        }
+    }
+    let content = if dm {
+        "I could not DM you, so here is your review instead."
+    } else {
+        starting
+    };
+    let handle = ctx
+        .send(CreateReply::default().content(content).ephemeral(true))
+        .await?;
+    Ok(Surface::Reply(handle))
+}
+
+/// Index of the button of `custom_ids` the invoking user presses, or `None`
+/// after two minutes without a press.
+async fn press(ctx: Context<'_>, custom_ids: &[String]) -> Result<Option<usize>, Error> {
+    let Some(press) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .custom_ids(custom_ids.to_vec())
+        .timeout(Duration::from_secs(120))
+        .await
+    else {
+        return Ok(None);
+    };
+    press
+        .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+        .await?;
+    Ok(custom_ids.iter().position(|id| *id == press.data.custom_id))
+}
+
+fn front(progress: &str, word: &Word) -> String {
+    format!(
+        "{progress}\n# {}\nRecall its reading, then show the answer.",
+        word.headword
+    )
+}
+
+fn back(progress: &str, word: &Word) -> String {
+    format!(
+        "{progress}\n# {}\n**{}**\nHow well did you remember it?",
+        word.headword, word.reading
+    )
+}
@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use poise::serenity_prelude as serenity;
+use poise::CreateReply;
+
+use super::placeholder;
+use crate::backend::{DictionaryBackend, SearchHit};
+use crate::render::truncate;
+use crate::text::{self, is_hangul};
+use crate::{Context, Error};
+
+/// Longest word, in syllables, looked up as a whole.
+const MAX_WORD: usize = 4;
+/// Most select menus Discord shows under one message.
+const MAX_MENUS: usize = 5;
+/// Longest text accepted, in hangul syllables, as each may take several
+/// lookups.
+const MAX_SYLLABLES: usize = 100;
+
+/// Piece of the input: a word with its hanja spellings, or text kept as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub text: String,
+    /// Spellings of `text`, most likely first; empty for text kept as is.
+    pub candidates: Vec<SearchHit>,
+}
+
+/// Suggest hanja for the words of a hangul text
+#[poise::command(
+    prefix_command,
+    slash_command,
+    track_edits,
+    required_permissions = "SEND_MESSAGES"
+)]
+pub async fn tohanja(ctx: Context<'_>, #[rest] text: String) -> Result<(), Error> {
+    if text.chars().filter(|&c| is_hangul(c)).count() > MAX_SYLLABLES {
+        ctx.say(format!(
+            "Please give at most {MAX_SYLLABLES} hangul syllables at a time."
+        ))
+        .await?;
+        return Ok(());
+    }
+    let result = placeholder(ctx, &text).await?;
+    let segments = segment(&*ctx.data().backend, &text).await;
+    // Index of the chosen candidate of each segment; `None` keeps the hangul.
+    let mut choices = segments
+        .iter()
+        .map(|segment| (!segment.candidates.is_empty()).then_some(0))
+        .collect::<Vec<_>>();
+    let words = segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| !segment.candidates.is_empty())
+        .map(|(i, _)| i)
+        .take(MAX_MENUS)
+        .collect::<Vec<_>>();
+    if words.is_empty() {
+        result
+            .edit(
+                ctx,
+                CreateReply::default().content("No hanja words were found."),
+            )
+            .await?;
+        return Ok(());
+    }
+
+    let menu_id = |i: usize| format!("{}:word:{i}", ctx.id());
+    let menus = |choices: &[Option<usize>]| {
+        words
+            .iter()
+            .map(|&i| {
+                let segment = &segments[i];
+                let mut options =
+                    vec![
+                        serenity::CreateSelectMenuOption::new(truncate(&segment.text, 100), "-")
+                            .description("Keep in hangul")
+                            .default_selection(choices[i].is_none()),
+                    ];
+                for (j, hit) in segment.candidates.iter().enumerate().take(24) {
+                    let option = serenity::CreateSelectMenuOption::new(
+                        truncate(&hit.headword, 100),
+                        j.to_string(),
+                    )
+                    .default_selection(choices[i] == Some(j));
+                    options.push(match &hit.gloss {
+                        Some(gloss) => option.description(truncate(gloss, 100)),
+                        None => option,
+                    });
+                }
+                serenity::CreateActionRow::SelectMenu(serenity::CreateSelectMenu::new(
+                    menu_id(i),
+                    serenity::CreateSelectMenuKind::String { options },
+                ))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    result
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(converted(&segments, &choices))
+                .components(menus(&choices)),
+        )
+        .await?;
+    let ids = words.iter().map(|&i| menu_id(i)).collect::<Vec<_>>();
+    while let Some(interaction) = serenity::ComponentInteractionCollector::new(ctx)
+        .author_id(ctx.author().id)
+        .channel_id(ctx.channel_id())
+        .custom_ids(ids.clone())
+        .timeout(Duration::from_secs(120))
+        .await
+    {
+        interaction
+            .create_response(ctx, serenity::CreateInteractionResponse::Acknowledge)
+            .await?;
+        let (Some(i), serenity::ComponentInteractionDataKind::StringSelect { values }) = (
+            ids.iter().position(|id| *id == interaction.data.custom_id),
+            &interaction.data.kind,
+        ) else {
+            continue;
+        };
+        choices[words[i]] = values.first().and_then(|value| value.parse().ok());
+        result
+            .edit(
+                ctx,
+                CreateReply::default()
+                    .content(converted(&segments, &choices))
+                    .components(menus(&choices)),
+            )
+            .await?;
+    }
+    result
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(converted(&segments, &choices))
+                .components(vec![]),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Split `text` into words with hanja spellings and text kept as is.
+///
+/// Each run of hangul is covered from the left by the longest words of two
+/// to [`MAX_WORD`] syllables that the dictionary knows; what is left, like
+/// particles, is kept.
+pub async fn segment(backend: &dyn DictionaryBackend, text: &str) -> Vec<Segment> {
+    let mut segments = Vec::<Segment>::new();
+    let keep = |segments: &mut Vec<Segment>, kept: &str| match segments.last_mut() {
+        Some(last) if last.candidates.is_empty() => last.text.push_str(kept),
+        _ => segments.push(Segment {
+            text: kept.to_string(),
+            candidates: Vec::new(),
+        }),
+    };
+    for (hangul, run) in text::runs(text, is_hangul) {
+        if !hangul {
+            keep(&mut segments, run);
+            continue;
+        }
+        let syllables = run.chars().collect::<Vec<_>>();
+        let mut start = 0;
+        while start < syllables.len() {
+            let mut found = None;
+            for len in (2..=MAX_WORD.min(syllables.len() - start)).rev() {
+                let word = syllables[start..start + len].iter().collect::<String>();
+                match backend.by_reading(&word).await {
+                    Ok(hits) if !hits.is_empty() => {
+                        found = Some((len, word, hits));
+                        break;
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to look up {word} for its hanja: {e}"),
+                }
+            }
+            match found {
+                Some((len, text, candidates)) => {
+                    segments.push(Segment { text, candidates });
+                    start += len;
+                }
+                None => {
+                    keep(&mut segments, &syllables[start].to_string());
+                    start += 1;
+                }
+            }
+        }
+    }
+    segments
+}
+
+/// `segments` spelled with the chosen candidates.
+fn converted(segments: &[Segment], choices: &[Option<usize>]) -> String {
+    let text = segments
+        .iter()
+        .zip(choices)
+        .map(|(segment, choice)| {
+            choice
+                .and_then(|j| segment.candidates.get(j))
+                .map_or(segment.text.as_str(), |hit| hit.headword.as_str())
+        })
+        .collect::<String>();
+    truncate(&text, 2000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::mock_daum::MockDaum;
+
+    const SEARCH_DAEHANMINGUK: &str = include_str!("../../fixtures/daum/search_daehanminguk.html");
+    const SEARCH_GUKMIN: &str = include_str!("../../fixtures/daum/search_gukmin.html");
+    const SEARCH_SAGI: &str = include_str!("../../fixtures/daum/search_sagi.html");
+
+    #[tokio::test]
+    async fn segments_into_words() {
+        let server = MockDaum::start(vec![
+            (
+                "/search.do?dic=hanja&q=%EB%8C%80%ED%95%9C%EB%AF%BC%EA%B5%AD",
+                SEARCH_DAEHANMINGUK,
+            ),
+            ("/search.do?dic=hanja&q=%EA%B5%AD%EB%AF%BC", SEARCH_GUKMIN),
+            ("/search.do?dic=hanja&q=%EC%82%AC%EA%B8%B0", SEARCH_SAGI),
+        ])
+        .await;
+        let backend = server.backend();
+
+        let segments = segment(&backend, "대한민국 국민의 사기!").await;
+        let words = segments
+            .iter()
+            .map(|segment| {
+                let candidates = segment
+                    .candidates
+                    .iter()
+                    .map(|hit| hit.headword.as_str())
+                    .collect::<Vec<_>>();
+                (segment.text.as_str(), candidates)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            [
+                ("대한민국", vec!["大韓民國"]),
+                (" ", vec![]),
+                ("국민", vec!["國民"]),
+                ("의 ", vec![]),
+                ("사기", vec!["士氣", "詐欺", "史記", "沙器"]),
+                ("!", vec![]),
+            ]
+        );
+
+        let choices = [Some(0), None, Some(0), None, Some(1), None];
+        assert_eq!(converted(&segments, &choices), "大韓民國 國民의 詐欺!");
+        let choices = [Some(0), None, None, None, Some(0), None];
+        assert_eq!(converted(&segments, &choices), "大韓民國 국민의 士氣!");
+    }
+}
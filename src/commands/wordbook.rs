@@ -0,0 +1,78 @@
+use poise::CreateReply;
+
+use super::navigate;
+use crate::{render, Context, Error};
+
+/// Words shown on each page of `wordbook list`.
+const WORDS_PER_PAGE: usize = 10;
+
+/// Review the hanja you saved with the 💾 Save button
+#[poise::command(
+    prefix_command,
+    slash_command,
+    subcommands("list", "remove", "clear"),
+    subcommand_required
+)]
+pub async fn wordbook(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// List the hanja in your wordbook, most recently saved first
+#[poise::command(prefix_command, slash_command, ephemeral)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let words = ctx.data().store.words(ctx.author().id)?;
+    let render =
+        |page| CreateReply::default().content(render::wordbook(&words, page, WORDS_PER_PAGE));
+    let handle = ctx.send(render(0)).await?;
+    let pages = words.len().div_ceil(WORDS_PER_PAGE);
+    navigate(ctx, &handle, 0, pages, &[], |page| {
+        std::future::ready(Ok(render(page)))
+    })
+    .await?;
+    Ok(())
+}
+
+/// Remove a hanja from your wordbook
+#[poise::command(prefix_command, slash_command, ephemeral)]
+pub async fn remove(ctx: Context<'_>, hanja: String) -> Result<(), Error> {
+    let hanja = hanja.trim();
+    let content = if ctx.data().store.remove_word(ctx.author().id, hanja)? {
+        format!("Removed {hanja} from your wordbook.")
+    } else {
+        format!("{hanja} is not in your wordbook.")
+    };
+    ctx.say(content).await?;
+    Ok(())
+}
+
+/// Remove every hanja from your wordbook
+#[poise::command(prefix_command, slash_command, ephemeral)]
+pub async fn clear(ctx: Context<'_>) -> Result<(), Error> {
+    let count = ctx.data().store.words(ctx.author().id)?.len();
+    if count == 0 {
+        ctx.say("Your wordbook is already empty.").await?;
+        return Ok(());
+    }
+    let prompt = CreateReply::default().content(format!(
+        "This removes all {count} hanja from your wordbook. Are you sure?"
+    ));
+    let handle = ctx.send(prompt.clone()).await?;
+    let confirm = [format!("Clear {count} hanja")];
+    let Some(_) = navigate(ctx, &handle, 0, 1, &confirm, |_| {
+        std::future::ready(Ok(prompt.clone()))
+    })
+    .await?
+    else {
+        return Ok(());
+    };
+    let cleared = ctx.data().store.clear_words(ctx.author().id)?;
+    handle
+        .edit(
+            ctx,
+            CreateReply::default()
+                .content(format!("Cleared {cleared} hanja from your wordbook."))
+                .components(vec![]),
+        )
+        .await?;
+    Ok(())
+}
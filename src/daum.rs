@@ -0,0 +1,420 @@
+//! Parsers for the pages served by the Daum hanja dictionary.
+//!
+//! Every function here works on raw HTML strings so that it can be tested
+//! against the pages in `fixtures/daum` without touching the network. Those
+//! pages are still hand-written; see the README there before trusting a
+//! selector that only they exercise.
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::backend::SearchHit;
+use crate::entry::{Example, HanjaEntry, MeaningGroup, Radical, Relation, RelationKind};
+
+/// Selector for the reading on `view.do` pages.
+pub const READING: &str = ".txt_read";
+
+pub struct Parser {
+    card: Selector,
+    link: Selector,
+    sub_read: Selector,
+    gloss: Selector,
+    read: Selector,
+    part_of_speech: Selector,
+    ruby: Selector,
+    reading: Selector,
+    refer_title: Selector,
+    refer: Selector,
+    info_term: Selector,
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self {
+            card: Selector::parse(".card_word").unwrap(),
+            link: Selector::parse(r#"a[href*="/word/view.do?wordid="]"#).unwrap(),
+            sub_read: Selector::parse(".sub_read").unwrap(),
+            gloss: Selector::parse(".list_search li").unwrap(),
+            read: Selector::parse(READING).unwrap(),
+            part_of_speech: Selector::parse(".txt_pos").unwrap(),
+            ruby: Selector::parse(".desc_ruby").unwrap(),
+            reading: Selector::parse(".desc_ex").unwrap(),
+            refer_title: Selector::parse(".txt_emph3").unwrap(),
+            refer: Selector::parse(".txt_refer.on").unwrap(),
+            info_term: Selector::parse(".list_info dt").unwrap(),
+        }
+    }
+
+    /// List the entries of a `search.do` page in order.
+    ///
+    /// Entries are found by their `/word/view.do?wordid=` links, which is
+    /// what the bot has always relied on. Readings and glosses are taken from
+    /// the `.card_word` around a link when there is one and left out
+    /// otherwise, so a change to that markup cannot hide the entries.
+    pub fn search(&self, html: &str) -> Vec<SearchHit> {
+        let document = Html::parse_document(html);
+        let mut hits: Vec<SearchHit> = Vec::new();
+        for link in document.select(&self.link) {
+            let Some((_, id)) = link
+                .attr("href")
+                .and_then(|href| href.split_once("wordid="))
+            else {
+                continue;
+            };
+            let id = id.split('&').next().unwrap_or(id);
+            let headword = extract_text(link);
+            if headword.is_empty() || hits.iter().any(|hit| hit.id == id) {
+                continue;
+            }
+            let card = link
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .find(|element| self.card.matches(element));
+            let glosses = card
+                .into_iter()
+                .flat_map(|card| card.select(&self.gloss))
+                .map(|li| {
+                    extract_text(li)
+                        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.')
+                        .trim()
+                        .to_string()
+                })
+                .filter(|gloss| !gloss.is_empty())
+                .collect::<Vec<_>>();
+            hits.push(SearchHit {
+                id: id.to_string(),
+                headword,
+                reading: card
+                    .and_then(|card| card.select(&self.sub_read).next())
+                    .map(extract_text),
+                gloss: (!glosses.is_empty()).then(|| glosses.join(", ")),
+                source: "daum".to_string(),
+            });
+        }
+        hits
+    }
+
+    /// Extract the reading from a `view.do` page.
+    pub fn reading(&self, html: &str) -> Option<String> {
+        let document = Html::parse_document(html);
+        let read = document.select(&self.read).next()?;
+        Some(read.text().collect::<String>().trim().to_string())
+    }
+
+    /// Extract the radical (부수) and total stroke count (획수) from a
+    /// `view.do` page.
+    pub fn composition(&self, html: &str) -> (Option<Radical>, Option<u8>) {
+        let document = Html::parse_document(html);
+        let mut radical = None;
+        let mut strokes = None;
+        for term in document.select(&self.info_term) {
+            let Some(value) = term.next_siblings().find_map(ElementRef::wrap) else {
+                continue;
+            };
+            let value = extract_text(value);
+            match extract_text(term).as_str() {
+                // `子 (아들자, 3획)`: the radical and its own stroke count.
+                "부수" => {
+                    radical = value.split_whitespace().next().map(|character| {
+                        let own = value
+                            .rsplit_once(", ")
+                            .and_then(|(_, own)| parse_strokes(own.trim_end_matches(')')));
+                        (character.to_string(), own)
+                    });
+                }
+                "획수" => strokes = parse_strokes(&value),
+                _ => {}
+            }
+        }
+        let radical = radical.map(|(character, own)| Radical {
+            character,
+            remaining_strokes: strokes
+                .zip(own)
+                .and_then(|(total, own)| total.checked_sub(own)),
+        });
+        (radical, strokes)
+    }
+
+    /// Fill meanings, examples and related characters from a `view_supword.do` fragment.
+    pub fn supword(&self, html: &str, entry: &mut HanjaEntry) {
+        let document = Html::parse_fragment(html);
+        let mut children = document
+            .root_element()
+            .child_elements()
+            .flat_map(|elem| elem.child_elements());
+        while let Some(child) = children.next() {
+            let class = child.attr("class");
+            if self.part_of_speech.matches(&child) {
+                entry.meanings.push(MeaningGroup {
+                    part_of_speech: Some(extract_text(child)),
+                    senses: Vec::new(),
+                });
+            } else if class == Some("wrap_ex") {
+                let label = extract_text(child);
+                if label.is_empty() {
+                    continue;
+                }
+                // The sense number sits in `wrap_ex` and its text in the next sibling.
+                let sense = match children.next() {
+                    Some(text) if is_sense_number(&label) => extract_text(text),
+                    Some(text) => format!("{label} {}", extract_text(text)),
+                    None => label,
+                };
+                match entry.meanings.last_mut() {
+                    Some(group) => group.senses.push(sense),
+                    None => entry.meanings.push(MeaningGroup {
+                        part_of_speech: None,
+                        senses: vec![sense],
+                    }),
+                }
+            } else if class == Some("item_example") {
+                for li in child.child_elements() {
+                    if let Some(example) = self.example(li) {
+                        entry.examples.push(example);
+                    }
+                }
+            } else if class == Some("ex_refer") {
+                let Some(title) = child.select(&self.refer_title).next() else {
+                    continue;
+                };
+                let characters = child
+                    .select(&self.refer)
+                    .map(extract_text)
+                    .collect::<Vec<_>>();
+                if !characters.is_empty() {
+                    entry.relations.push(Relation {
+                        kind: RelationKind::from_title(&extract_text(title)),
+                        characters,
+                    });
+                }
+            }
+        }
+    }
+
+    fn example(&self, li: ElementRef) -> Option<Example> {
+        let ruby = li.select(&self.ruby).next()?;
+        let mut source = None;
+        let mut phrase = String::new();
+        for s in ruby.text() {
+            if s.starts_with('\u{00a0}') && s.ends_with('\u{00a0}') {
+                source = Some(s.trim().to_string());
+            } else {
+                phrase.push_str(s);
+            }
+        }
+        Some(Example {
+            phrase: phrase.trim().to_string(),
+            reading: li.select(&self.reading).next().map(extract_text),
+            source,
+        })
+    }
+}
+
+/// Parse a stroke count like `16획`.
+fn parse_strokes(text: &str) -> Option<u8> {
+    text.trim().strip_suffix('획')?.trim().parse().ok()
+}
+
+fn is_sense_number(label: &str) -> bool {
+    label
+        .chars()
+        .all(|c| c.is_ascii_digit() || c == '.' || c.is_whitespace())
+}
+
+fn extract_text(element: ElementRef) -> String {
+    element.text().collect::<String>().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::plausible;
+
+    const SEARCH_HAK: &str = include_str!("../fixtures/daum/search_hak.html");
+    const SEARCH_NO_RESULT: &str = include_str!("../fixtures/daum/search_no_result.html");
+    const SEARCH_MISMATCH: &str = include_str!("../fixtures/daum/search_mismatch.html");
+    const SEARCH_RAK: &str = include_str!("../fixtures/daum/search_rak.html");
+    const VIEW_HAK: &str = include_str!("../fixtures/daum/view_hak.html");
+    const VIEW_MALFORMED: &str = include_str!("../fixtures/daum/view_malformed.html");
+    const SUPWORD_HAK: &str = include_str!("../fixtures/daum/supword_hak.html");
+    const SUPWORD_EMPTY: &str = include_str!("../fixtures/daum/supword_empty.html");
+
+    fn entry(parser: &Parser, view: &str, supword: &str) -> Option<HanjaEntry> {
+        let mut entry = HanjaEntry::new("學".to_string(), parser.reading(view)?);
+        parser.supword(supword, &mut entry);
+        Some(entry)
+    }
+
+    #[test]
+    fn search_lists_cards() {
+        let parser = Parser::new();
+        assert_eq!(
+            parser.search(SEARCH_HAK),
+            [
+                SearchHit {
+                    id: "hjdic_0001234".to_string(),
+                    headword: "學".to_string(),
+                    reading: Some("학".to_string()),
+                    gloss: Some("배울 학".to_string()),
+                    source: "daum".to_string(),
+                },
+                SearchHit {
+                    id: "hjdic_0004567".to_string(),
+                    headword: "學校".to_string(),
+                    reading: Some("학교".to_string()),
+                    gloss: Some("학생을 가르치는 기관".to_string()),
+                    source: "daum".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn search_with_homographs() {
+        let parser = Parser::new();
+        let hits = parser.search(SEARCH_RAK);
+        let readings = hits
+            .iter()
+            .map(|hit| hit.reading.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(readings, ["락", "악", "요", "낙원"]);
+        assert_eq!(hits[1].gloss.as_deref(), Some("노래 악, 풍류 악"));
+        assert_eq!(hits[3].gloss, None);
+    }
+
+    #[test]
+    fn search_without_cards() {
+        let parser = Parser::new();
+        let html = r#"<div class="search_box">
+            <a href="/word/view.do?wordid=hjdic_0001234&q=%E5%AD%B8" class="txt_cleansch"><span class="txt_emph1">學</span></a>
+            <a href="/word/view.do?wordid=hjdic_0001234" class="more">學</a>
+            <a href="/word/view.do?wordid=hjdic_0004567"><span class="txt_emph1">學</span>校</a>
+        </div>"#;
+        assert_eq!(
+            parser.search(html),
+            [
+                SearchHit {
+                    id: "hjdic_0001234".to_string(),
+                    headword: "學".to_string(),
+                    reading: None,
+                    gloss: None,
+                    source: "daum".to_string(),
+                },
+                SearchHit {
+                    id: "hjdic_0004567".to_string(),
+                    headword: "學校".to_string(),
+                    reading: None,
+                    gloss: None,
+                    source: "daum".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn search_without_links() {
+        let parser = Parser::new();
+        assert!(parser.search(SEARCH_NO_RESULT).is_empty());
+    }
+
+    #[test]
+    fn plausible_candidates() {
+        let parser = Parser::new();
+        assert_eq!(plausible("學", parser.search(SEARCH_HAK)).len(), 1);
+        assert_eq!(plausible("樂", parser.search(SEARCH_RAK)).len(), 3);
+        assert_eq!(plausible("樂園", parser.search(SEARCH_RAK)).len(), 1);
+        assert!(plausible("斈", parser.search(SEARCH_MISMATCH)).is_empty());
+    }
+
+    #[test]
+    fn reading_is_trimmed() {
+        let parser = Parser::new();
+        assert_eq!(parser.reading(VIEW_HAK).as_deref(), Some("배울 학"));
+        assert_eq!(parser.reading(VIEW_MALFORMED), None);
+    }
+
+    #[test]
+    fn composition_from_view() {
+        let parser = Parser::new();
+        assert_eq!(
+            parser.composition(VIEW_HAK),
+            (
+                Some(Radical {
+                    character: "子".to_string(),
+                    remaining_strokes: Some(13),
+                }),
+                Some(16)
+            )
+        );
+        assert_eq!(parser.composition(VIEW_MALFORMED), (None, None));
+    }
+
+    #[test]
+    fn entry_from_supword() {
+        let parser = Parser::new();
+        let entry = entry(&parser, VIEW_HAK, SUPWORD_HAK).unwrap();
+        assert_eq!(entry.headword, "學");
+        assert_eq!(entry.reading, "배울 학");
+        assert_eq!(
+            entry.meanings,
+            [
+                MeaningGroup {
+                    part_of_speech: Some("동사".to_string()),
+                    senses: vec![
+                        "배우다. 글을 읽고 익히다.".to_string(),
+                        "가르치다.".to_string(),
+                    ],
+                },
+                MeaningGroup {
+                    part_of_speech: Some("명사".to_string()),
+                    senses: vec!["학문. 학교.".to_string()],
+                },
+            ]
+        );
+        assert_eq!(
+            entry.examples,
+            [
+                Example {
+                    phrase: "學而時習之".to_string(),
+                    reading: Some("학이시습지".to_string()),
+                    source: Some("論語".to_string()),
+                },
+                Example {
+                    phrase: "敎學相長".to_string(),
+                    reading: Some("교학상장".to_string()),
+                    source: None,
+                },
+            ]
+        );
+        let relations = entry
+            .relations
+            .iter()
+            .map(|relation| (relation.kind.clone(), relation.characters.concat()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            relations,
+            [
+                (RelationKind::Synonym, "習修".to_string()),
+                (RelationKind::Antonym, "敎".to_string()),
+                (RelationKind::Vulgar, "斈".to_string()),
+                (RelationKind::Abbreviation, "学".to_string()),
+                (RelationKind::Simplified, "学".to_string()),
+                (RelationKind::Other("고자".to_string()), "斅".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn entry_with_empty_supword() {
+        let parser = Parser::new();
+        let entry = entry(&parser, VIEW_HAK, SUPWORD_EMPTY).unwrap();
+        assert!(entry.meanings.is_empty());
+        assert!(entry.examples.is_empty());
+        assert!(entry.relations.is_empty());
+    }
+
+    #[test]
+    fn entry_without_reading() {
+        let parser = Parser::new();
+        assert_eq!(entry(&parser, VIEW_MALFORMED, SUPWORD_HAK), None);
+    }
+}
@@ -0,0 +1,127 @@
+//! Backend-independent model of a dictionary entry.
+//!
+//! Parsers fill these types without any formatting; see `render` for the
+//! presentation side.
+
+use serde::{Deserialize, Serialize};
+
+use crate::grade::Grade;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HanjaEntry {
+    pub headword: String,
+    pub reading: String,
+    pub meanings: Vec<MeaningGroup>,
+    pub examples: Vec<Example>,
+    /// Related characters, one group per kind of relation.
+    #[serde(default)]
+    pub relations: Vec<Relation>,
+    pub radical: Option<Radical>,
+    pub strokes: Option<u8>,
+    /// Dictionary the entry was taken from.
+    #[serde(default)]
+    pub source: Option<Source>,
+    /// 교육용 기초한자 and 한자능력검정시험 levels of a single character.
+    #[serde(default)]
+    pub grade: Option<Grade>,
+    /// Per-character breakdown of a compound word.
+    #[serde(default)]
+    pub characters: Vec<CharacterGloss>,
+}
+
+impl HanjaEntry {
+    pub fn new(headword: String, reading: String) -> Self {
+        Self {
+            headword,
+            reading,
+            meanings: Vec::new(),
+            examples: Vec::new(),
+            relations: Vec::new(),
+            radical: None,
+            strokes: None,
+            source: None,
+            grade: None,
+            characters: Vec::new(),
+        }
+    }
+}
+
+/// Reading and core meaning of one character of a compound.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharacterGloss {
+    pub character: String,
+    pub reading: String,
+    pub meaning: Option<String>,
+}
+
+/// Senses sharing a part of speech, in dictionary order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeaningGroup {
+    pub part_of_speech: Option<String>,
+    pub senses: Vec<String>,
+}
+
+/// Example phrase using the entry, e.g. `學而時習之` from 論語.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Example {
+    pub phrase: String,
+    pub reading: Option<String>,
+    pub source: Option<String>,
+}
+
+/// Characters standing in one relation to the entry, e.g. its 유의자.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Relation {
+    pub kind: RelationKind,
+    pub characters: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RelationKind {
+    /// 유의자
+    Synonym,
+    /// 반대자
+    Antonym,
+    /// 상대자
+    Counterpart,
+    /// 동자, a character used interchangeably.
+    Same,
+    /// 약자
+    Abbreviation,
+    /// 속자
+    Vulgar,
+    /// 간체자
+    Simplified,
+    /// Any other titled block, with its title.
+    Other(String),
+}
+
+impl RelationKind {
+    /// Kind of a reference block titled `title` in the dictionary.
+    pub fn from_title(title: &str) -> Self {
+        match title {
+            "유의자" => Self::Synonym,
+            "반대자" => Self::Antonym,
+            "상대자" => Self::Counterpart,
+            "동자" => Self::Same,
+            "약자" => Self::Abbreviation,
+            "속자" => Self::Vulgar,
+            "간체자" => Self::Simplified,
+            title => Self::Other(title.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Radical {
+    pub character: String,
+    /// Strokes of the character not counted in the radical.
+    pub remaining_strokes: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Source {
+    pub name: String,
+    /// Page of the entry in the dictionary.
+    pub url: Option<String>,
+}
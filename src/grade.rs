@@ -0,0 +1,293 @@
+//! 교육용 기초한자 and 한자능력검정시험 (급수) levels of characters.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+const BUNDLED: &str = include_str!("../data/grades.txt");
+
+/// Characters of the 교육용 기초한자 taught at each [`School`].
+const BASIC_PER_SCHOOL: usize = 900;
+
+/// School level at which a character is taught as 교육용 기초한자.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum School {
+    /// 중학교, the first 900 characters.
+    Middle,
+    /// 고등학교, the other 900 of the 1800.
+    High,
+}
+
+/// 한자능력검정시험 급수, ordered from the easiest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Level {
+    Grade8,
+    Grade7Plus,
+    Grade7,
+    Grade6Plus,
+    Grade6,
+    Grade5Plus,
+    Grade5,
+    Grade4Plus,
+    Grade4,
+    Grade3Plus,
+    Grade3,
+    Grade2,
+    Grade1,
+    SpecialPlus,
+    Special,
+}
+
+const LEVELS: [(Level, &str); 15] = [
+    (Level::Grade8, "8급"),
+    (Level::Grade7Plus, "7급Ⅱ"),
+    (Level::Grade7, "7급"),
+    (Level::Grade6Plus, "6급Ⅱ"),
+    (Level::Grade6, "6급"),
+    (Level::Grade5Plus, "5급Ⅱ"),
+    (Level::Grade5, "5급"),
+    (Level::Grade4Plus, "4급Ⅱ"),
+    (Level::Grade4, "4급"),
+    (Level::Grade3Plus, "3급Ⅱ"),
+    (Level::Grade3, "3급"),
+    (Level::Grade2, "2급"),
+    (Level::Grade1, "1급"),
+    (Level::SpecialPlus, "특급Ⅱ"),
+    (Level::Special, "특급"),
+];
+
+impl Level {
+    /// Parse `6급`, also written `6급2`, `6급II` or `준6급` for 6급Ⅱ.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let label = match text.strip_prefix('준') {
+            Some(level) => format!("{level}Ⅱ"),
+            None => match text.split_once('급') {
+                Some((rank, "II" | "2")) => format!("{rank}급Ⅱ"),
+                _ => text.to_string(),
+            },
+        };
+        LEVELS
+            .iter()
+            .find(|(_, known)| *known == label)
+            .map(|(level, _)| *level)
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, label) = LEVELS.iter().find(|(level, _)| level == self).unwrap();
+        f.write_str(label)
+    }
+}
+
+impl fmt::Display for School {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Middle => "중학교",
+            Self::High => "고등학교",
+        })
+    }
+}
+
+/// Where a character stands in both lists.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grade {
+    pub school: Option<School>,
+    pub level: Option<Level>,
+}
+
+/// Condition on [`Grade`] given to commands, e.g. `6급` or `중학교`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// At most this hard.
+    Level(Level),
+    /// Taught by the end of this school.
+    School(School),
+}
+
+impl Filter {
+    pub fn admits(&self, grade: Grade) -> bool {
+        match self {
+            Self::Level(max) => grade.level.is_some_and(|level| level <= *max),
+            Self::School(max) => grade.school.is_some_and(|school| school <= *max),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = InvalidFilter;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        match text.trim() {
+            "중학교" | "중학" | "중" => Ok(Self::School(School::Middle)),
+            "고등학교" | "고등" | "고" => Ok(Self::School(School::High)),
+            text => Level::parse(text).map(Self::Level).ok_or(InvalidFilter),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidFilter;
+
+impl fmt::Display for InvalidFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected a level such as `6급`, `준5급`, `중학교` or `고등학교`"
+        )
+    }
+}
+
+impl std::error::Error for InvalidFilter {}
+
+/// Table of [`Grade`]s by character.
+#[derive(Debug, Default)]
+pub struct Grades {
+    grades: HashMap<char, Grade>,
+}
+
+impl Grades {
+    /// The table shipped in `data/grades.txt`.
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Parse `character<TAB>school<TAB>level` lines, skipping comments and
+    /// lines without a single character.
+    pub fn parse(text: &str) -> Self {
+        let mut grades = HashMap::new();
+        for line in text.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split('\t');
+            let mut chars = fields.next().unwrap_or_default().chars();
+            let (Some(character), None) = (chars.next(), chars.next()) else {
+                continue;
+            };
+            let school = match fields.next().map(str::trim) {
+                Some("중학교") => Some(School::Middle),
+                Some("고등학교") => Some(School::High),
+                _ => None,
+            };
+            let level = fields.next().and_then(Level::parse);
+            grades.insert(character, Grade { school, level });
+        }
+        Self { grades }
+    }
+
+    /// Grade of a single-character `headword`; compounds have none.
+    pub fn get(&self, headword: &str) -> Option<Grade> {
+        let mut chars = headword.chars();
+        match (chars.next(), chars.next()) {
+            (Some(character), None) => self.grades.get(&character).copied(),
+            _ => None,
+        }
+    }
+
+    /// Whether `filter` admits `headword`; ungraded characters never pass.
+    pub fn admits(&self, filter: Filter, headword: &str) -> bool {
+        filter.admits(self.get(headword).unwrap_or_default())
+    }
+
+    /// Whether all 1800 기초한자 are listed, 900 for each school.
+    ///
+    /// The bundled table is not: it only covers the bundled Unihan subset.
+    pub fn is_complete(&self) -> bool {
+        [School::Middle, School::High].into_iter().all(|school| {
+            let listed = self
+                .grades
+                .values()
+                .filter(|grade| grade.school == Some(school))
+                .count();
+            listed == BASIC_PER_SCHOOL
+        })
+    }
+
+    /// Characters admitted by `filter`, or all of them, in code point order.
+    pub fn characters(&self, filter: Option<Filter>) -> Vec<char> {
+        let mut characters = self
+            .grades
+            .iter()
+            .filter(|(_, grade)| filter.is_none_or(|filter| filter.admits(**grade)))
+            .map(|(character, _)| *character)
+            .collect::<Vec<_>>();
+        characters.sort_unstable();
+        characters
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_levels() {
+        assert_eq!(Level::parse("8급"), Some(Level::Grade8));
+        assert_eq!(Level::parse("6급Ⅱ"), Some(Level::Grade6Plus));
+        assert_eq!(Level::parse("6급II"), Some(Level::Grade6Plus));
+        assert_eq!(Level::parse("6급2"), Some(Level::Grade6Plus));
+        assert_eq!(Level::parse("준6급"), Some(Level::Grade6Plus));
+        assert_eq!(Level::parse("2급"), Some(Level::Grade2));
+        assert_eq!(Level::parse("특급"), Some(Level::Special));
+        assert_eq!(Level::parse("9급"), None);
+        assert_eq!(Level::Grade7Plus.to_string(), "7급Ⅱ");
+    }
+
+    #[test]
+    fn filters() {
+        let grade = Grade {
+            school: Some(School::Middle),
+            level: Some(Level::Grade6),
+        };
+        assert!("5급".parse::<Filter>().unwrap().admits(grade));
+        assert!("6급".parse::<Filter>().unwrap().admits(grade));
+        assert!(!"6급Ⅱ".parse::<Filter>().unwrap().admits(grade));
+        assert!("고등학교".parse::<Filter>().unwrap().admits(grade));
+        assert!(!"중학교".parse::<Filter>().unwrap().admits(Grade::default()));
+        assert_eq!("학교".parse::<Filter>().ok(), None);
+    }
+
+    #[test]
+    fn bundled_table() {
+        let grades = Grades::bundled();
+        assert_eq!(
+            grades.get("學"),
+            Some(Grade {
+                school: Some(School::Middle),
+                level: Some(Level::Grade8),
+            })
+        );
+        assert_eq!(grades.get("韓").unwrap().school, Some(School::High));
+        assert_eq!(grades.get("學校"), None);
+        assert_eq!(grades.get("鶴"), None);
+        assert_eq!(grades.characters(None).len(), 70);
+        let high_school = grades.characters(Some(Filter::School(School::High)));
+        assert_eq!(high_school.len(), 70);
+        assert!(grades
+            .characters(Some(Filter::School(School::Middle)))
+            .iter()
+            .all(|&character| character != '韓'));
+        assert!(!grades.is_complete());
+    }
+
+    #[test]
+    fn complete_table() {
+        let table = (0..1800u32)
+            .map(|i| {
+                let school = if i < 900 { "중학교" } else { "고등학교" };
+                format!("{}\t{school}\t", char::from_u32(0x4E00 + i).unwrap())
+            })
+            .collect::<Vec<_>>();
+        assert!(Grades::parse(&table.join("\n")).is_complete());
+        assert!(!Grades::parse(&table[1..].join("\n")).is_complete());
+    }
+}
@@ -0,0 +1,137 @@
+//! 사자성어 (four-character idioms) with their meanings and origins.
+
+use std::path::Path;
+
+use rand::seq::SliceRandom;
+
+const BUNDLED: &str = include_str!("../data/idioms.txt");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Idiom {
+    pub idiom: String,
+    pub reading: String,
+    /// Word-for-word meaning of the characters.
+    pub literal: String,
+    /// What the idiom is used to say.
+    pub meaning: String,
+    /// Classic the idiom is taken from, e.g. `淮南子 人間訓`.
+    pub source: Option<String>,
+    /// Story behind the idiom.
+    pub origin: Option<String>,
+    pub related: Vec<String>,
+}
+
+/// Table of [`Idiom`]s in file order.
+#[derive(Debug, Default)]
+pub struct Idioms {
+    idioms: Vec<Idiom>,
+}
+
+impl Idioms {
+    /// The table shipped in `data/idioms.txt`.
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Parse tab-separated `idiom, reading, literal, meaning, source, origin,
+    /// related` lines, skipping comments and lines missing a meaning.
+    pub fn parse(text: &str) -> Self {
+        let mut idioms = Vec::new();
+        for line in text.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split('\t').map(str::trim);
+            let (Some(idiom), Some(reading), Some(literal), Some(meaning)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            if idiom.is_empty() || meaning.is_empty() {
+                continue;
+            }
+            let mut optional = || {
+                fields
+                    .next()
+                    .filter(|field| !field.is_empty())
+                    .map(str::to_string)
+            };
+            let source = optional();
+            let origin = optional();
+            let related = optional()
+                .map(|related| related.split(',').map(|r| r.trim().to_string()).collect())
+                .unwrap_or_default();
+            idioms.push(Idiom {
+                idiom: idiom.to_string(),
+                reading: reading.to_string(),
+                literal: literal.to_string(),
+                meaning: meaning.to_string(),
+                source,
+                origin,
+                related,
+            });
+        }
+        Self { idioms }
+    }
+
+    /// Idioms written or read as `query`, ignoring whitespace.
+    pub fn find(&self, query: &str) -> Vec<&Idiom> {
+        let query = query.split_whitespace().collect::<String>();
+        self.idioms
+            .iter()
+            .filter(|idiom| idiom.idiom == query || idiom.reading == query)
+            .collect()
+    }
+
+    /// The idiom written `idiom`.
+    pub fn get(&self, idiom: &str) -> Option<&Idiom> {
+        self.idioms.iter().find(|known| known.idiom == idiom)
+    }
+
+    pub fn random(&self) -> Option<&Idiom> {
+        self.idioms.choose(&mut rand::thread_rng())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_table() {
+        let idioms = Idioms::bundled();
+        assert!(idioms.idioms.len() > 150, "{}", idioms.idioms.len());
+        let found = idioms.find("새옹 지마");
+        assert_eq!(found.len(), 1);
+        let idiom = found[0];
+        assert_eq!(idiom.idiom, "塞翁之馬");
+        assert_eq!(idiom.literal, "변방 늙은이의 말.");
+        assert_eq!(idiom.source.as_deref(), Some("淮南子 人間訓"));
+        assert!(idiom.origin.is_some());
+        assert_eq!(idiom.related, ["轉禍爲福", "興盡悲來"]);
+        assert_eq!(idioms.find("塞翁之馬"), found);
+
+        let idiom = idioms.get("大器晩成").unwrap();
+        assert_eq!(idiom.origin, None);
+        assert!(idiom.related.is_empty());
+        assert!(idioms.find("학교").is_empty());
+        // Related idioms are listed in the table themselves.
+        for idiom in &idioms.idioms {
+            for related in &idiom.related {
+                assert!(idioms.get(related).is_some(), "{related}");
+            }
+        }
+    }
+
+    #[test]
+    fn parse_skips_incomplete_lines() {
+        let idioms = Idioms::parse("# comment\n一石二鳥\t일석이조\n\n一擧兩得\t일거양득\t한 번 들어 둘을 얻는다.\t두 이익을 얻음.\n");
+        assert_eq!(idioms.idioms.len(), 1);
+        assert_eq!(idioms.idioms[0].source, None);
+        assert_eq!(idioms.random(), idioms.idioms.first());
+    }
+}
@@ -1,198 +1,47 @@
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
 use anyhow::Context as _;
-use poise::{serenity_prelude as serenity, CreateReply};
-use scraper::{Html, Selector};
+use poise::serenity_prelude as serenity;
 use serenity::prelude::*;
 use shuttle_runtime::SecretStore;
 
+use backend::DictionaryBackend;
+
+mod backend;
+mod commands;
+mod daum;
+mod entry;
+mod grade;
+mod idiom;
+#[cfg(test)]
+mod mock_daum;
+mod radical;
+mod render;
+mod review;
+mod store;
+mod text;
+mod unihan;
+
 struct Data {
-    client: reqwest::Client,
-    hanja: Hanja,
+    backend: Box<dyn DictionaryBackend>,
+    emoji: render::Emoji,
+    grades: Arc<grade::Grades>,
+    idioms: idiom::Idioms,
+    store: Arc<store::Store>,
+    /// Channels where every message with hanja gets its reading, as saved in `store`.
+    passive_channels: Mutex<HashSet<serenity::ChannelId>>,
+    /// Last passive reading of each channel, to rate-limit them.
+    passive_cooldown: Mutex<commands::read::Cooldown>,
+    /// Quizzes running, by channel.
+    quizzes: Mutex<HashMap<serenity::ChannelId, commands::quiz::Session>>,
 }
+/// Database file used when `DATABASE_PATH` is not set.
+const DEFAULT_DATABASE_PATH: &str = "gajibot.sqlite3";
+
 type Error = Box<dyn std::error::Error + Send + Sync>;
 type Context<'a> = poise::Context<'a, Data, Error>;
 
-#[poise::command(prefix_command)]
-async fn ping(ctx: Context<'_>) -> Result<(), Error> {
-    ctx.say("Pong!").await?;
-    Ok(())
-}
-
-struct Hanja {
-    read: Selector,
-    ruby: Selector,
-    reading: Selector,
-    refer_title: Selector,
-    refer: Selector,
-}
-
-impl Hanja {
-    fn new() -> Self {
-        Self {
-            read: Selector::parse(".txt_read").unwrap(),
-            ruby: Selector::parse(".desc_ruby").unwrap(),
-            reading: Selector::parse(".desc_ex").unwrap(),
-            refer_title: Selector::parse(".txt_emph3").unwrap(),
-            refer: Selector::parse(".txt_refer.on").unwrap(),
-        }
-    }
-}
-
-/// Search hanja
-#[poise::command(
-    prefix_command,
-    slash_command,
-    track_edits,
-    required_permissions = "SEND_MESSAGES"
-)]
-async fn hanja(ctx: Context<'_>, hanja: String) -> Result<(), Error> {
-    struct HanjaInfo {
-        reading: String,
-        description: String,
-    }
-    let result = ctx
-        .reply(format!(
-            "Searching for {} <a:Loading:1363125483667193998>",
-            hanja
-        ))
-        .await?;
-    let Some(url_back) = ('entry: {
-        let search_list = ctx
-            .data()
-            .client
-            .get("https://dic.daum.net/search.do")
-            .query(&[("dic", "hanja"), ("q", &hanja)])
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        if let Some((_, link_start)) = search_list.split_once("/word/view.do?wordid=") {
-            if let Some((url_back, rest)) = link_start.split_once('"') {
-                match rest.split_once(r#"class="txt_emph1">"#) {
-                    Some((_, x)) if x.starts_with(&hanja) => {
-                        break 'entry Some(url_back.to_string())
-                    }
-                    _ => {}
-                }
-            }
-        }
-        None
-    }) else {
-        result
-            .edit(ctx, CreateReply::default().content("No result"))
-            .await?;
-        return Ok(());
-    };
-
-    let info = {
-        let referer = format!("https://dic.daum.net/word/view.do?wordid={url_back}");
-        let response = ctx.data().client.get(&referer).send().await?.text().await?;
-
-        let reading = {
-            let document = Html::parse_document(&response);
-            document
-                .select(&ctx.data().hanja.read)
-                .next()
-                .unwrap()
-                .text()
-                .collect::<String>()
-        };
-
-        let response = ctx
-            .data()
-            .client
-            .get(format!(
-                "https://dic.daum.net/word/view_supword.do?suptype=KUMSUNG_HH&wordid={url_back}"
-            ))
-            .header("Referer", referer)
-            .send()
-            .await?
-            .text()
-            .await?;
-
-        let document = Html::parse_fragment(&response);
-        let mut description = String::new();
-        let mut children = document
-            .root_element()
-            .child_elements()
-            .flat_map(|elem| elem.child_elements());
-        while let Some(child) = children.next() {
-            fn extract_text(text: scraper::element_ref::Text) -> String {
-                text.collect::<String>().trim().to_string()
-            }
-
-            let class = child.attr("class");
-            if class == Some("wrap_ex") {
-                let text = extract_text(child.text());
-                if text.is_empty() {
-                    continue;
-                }
-                description.push_str(&text);
-                if let Some(child) = children.next() {
-                    description.push_str(" ");
-                    description.push_str(&extract_text(child.text()));
-                }
-                description.push_str("\n");
-            } else if class == Some("item_example") {
-                for li in child.child_elements() {
-                    if let Some(ruby) = li.select(&ctx.data().hanja.ruby).next() {
-                        description.push_str("> ");
-                        let mut from = None;
-                        let mut phrase = String::new();
-                        for s in ruby.text() {
-                            if s.starts_with('\u{00a0}') && s.ends_with('\u{00a0}') {
-                                from = Some(s.trim());
-                            } else {
-                                phrase.push_str(s);
-                            }
-                        }
-                        description.push_str(phrase.trim());
-                        if let Some(example) = li.select(&ctx.data().hanja.reading).next() {
-                            description.push_str("(");
-                            description.push_str(&extract_text(example.text()));
-                            description.push_str(")");
-                        }
-                        if let Some(from) = from {
-                            description.push_str(" 《");
-                            description.push_str(from);
-                            description.push_str("》");
-                        }
-                        description.push_str("\n");
-                    }
-                }
-            } else if class == Some("ex_refer") {
-                if let Some(title) = child.select(&ctx.data().hanja.refer_title).next() {
-                    let title = extract_text(title.text());
-                    if title == "유의자" {
-                        description.push_str("<:rui:1363124010136764516> ");
-                        for refer in child.select(&ctx.data().hanja.refer) {
-                            description.push_str(&extract_text(refer.text()));
-                        }
-                        description.push_str("\n");
-                    }
-                }
-            }
-        }
-        HanjaInfo {
-            reading,
-            description,
-        }
-    };
-    result
-        .edit(
-            ctx,
-            CreateReply::default().content(format!(
-                "# {hanja}\n**{reading}**\n{description}",
-                reading = info.reading.trim(),
-                description = info.description
-            )),
-        )
-        .await?;
-    Ok(())
-}
-
 #[shuttle_runtime::main]
 async fn serenity(
     #[shuttle_runtime::Secrets] secrets: SecretStore,
@@ -202,14 +51,71 @@ async fn serenity(
         .get("DISCORD_TOKEN")
         .context("'DISCORD_TOKEN' was not found")?;
 
+    let grades = Arc::new(match secrets.get("GRADES_PATH") {
+        Some(path) => grade::Grades::load(&path)
+            .with_context(|| format!("failed to load grades from '{path}'"))?,
+        None => grade::Grades::bundled(),
+    });
+    if !grades.is_complete() {
+        tracing::warn!(
+            "The grade table lacks some of the 1800 기초한자, so most characters have no \
+             grade; set GRADES_PATH to a complete table"
+        );
+    }
+    let idioms = match secrets.get("IDIOMS_PATH") {
+        Some(path) => idiom::Idioms::load(&path)
+            .with_context(|| format!("failed to load idioms from '{path}'"))?,
+        None => idiom::Idioms::bundled(),
+    };
+
+    let database = secrets
+        .get("DATABASE_PATH")
+        .unwrap_or_else(|| DEFAULT_DATABASE_PATH.to_string());
+    let store = Arc::new(
+        store::Store::open(&database)
+            .with_context(|| format!("failed to open database '{database}'"))?,
+    );
+    let passive_channels = store
+        .passive_channels()
+        .context("failed to load passive channels")?;
+
+    // Choose where `hanja` looks entries up
+    let backend = backend::from_secrets(
+        &secrets,
+        backend::daum::client(backend::daum::REQUEST_TIMEOUT)
+            .context("failed to build the HTTP client")?,
+        grades.clone(),
+        store.clone(),
+    )
+    .map_err(|e| anyhow::anyhow!(e))?;
+    let emoji = render::Emoji::from_secrets(&secrets);
+
     // Set gateway intents, which decides what events the bot will be notified about
     let intents = GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT;
 
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![ping(), hanja()],
+            commands: vec![
+                commands::ping(),
+                commands::hanja(),
+                commands::eum(),
+                commands::hun(),
+                commands::radical(),
+                commands::read(),
+                commands::read_hanja(),
+                commands::passive(),
+                commands::tohanja(),
+                commands::idiom(),
+                commands::quiz(),
+                commands::wordbook(),
+                commands::review(),
+            ],
+            on_error: |error| Box::pin(commands::on_error(error)),
+            event_handler: |ctx, event, _framework, data| {
+                Box::pin(commands::on_event(ctx, event, data))
+            },
             prefix_options: poise::PrefixFrameworkOptions {
-                prefix: Some("gaji ".to_string()),
+                prefix: Some(commands::PREFIX.to_string()),
                 edit_tracker: Some(Arc::new(poise::EditTracker::for_timespan(
                     std::time::Duration::from_secs(3600),
                 ))),
@@ -221,8 +127,14 @@ async fn serenity(
             Box::pin(async move {
                 poise::builtins::register_globally(ctx, &framework.options().commands).await?;
                 Ok(Data {
-                    client: reqwest::Client::new(),
-                    hanja: Hanja::new(),
+                    backend,
+                    emoji,
+                    grades,
+                    idioms,
+                    store,
+                    passive_channels: Mutex::new(passive_channels),
+                    passive_cooldown: Mutex::default(),
+                    quizzes: Mutex::default(),
                 })
             })
         })
@@ -0,0 +1,89 @@
+//! Local HTTP stand-in for dic.daum.net serving recorded pages.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::backend::daum::{self, DaumBackend};
+
+/// Body of a route that accepts the request but never answers it.
+pub const NO_RESPONSE: &str = "\0no response";
+
+/// Request timeout of [`MockDaum::backend`], short enough for tests to wait out.
+pub const TIMEOUT: Duration = Duration::from_secs(1);
+
+type Routes = Arc<Vec<(&'static str, &'static str)>>;
+
+pub struct MockDaum {
+    base_url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockDaum {
+    /// Serve `routes` until the test ends.
+    ///
+    /// Each route is a prefix of the request target and the body to answer
+    /// with; the first matching route wins and anything else gets a 404.
+    pub async fn start(routes: Vec<(&'static str, &'static str)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let routes = Arc::new(routes);
+        tokio::spawn({
+            let requests = requests.clone();
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(serve(stream, routes.clone(), requests.clone()));
+                }
+            }
+        });
+        Self { base_url, requests }
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Daum backend pointed at this server, timing out after [`TIMEOUT`].
+    pub fn backend(&self) -> DaumBackend {
+        DaumBackend::new(daum::client(TIMEOUT).unwrap(), self.base_url())
+    }
+
+    /// Request targets received so far, in order.
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+async fn serve(mut stream: TcpStream, routes: Routes, requests: Arc<Mutex<Vec<String>>>) {
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        match stream.read(&mut buf).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => head.extend_from_slice(&buf[..n]),
+        }
+    }
+    let head = String::from_utf8_lossy(&head);
+    let Some(target) = head.split_whitespace().nth(1) else {
+        return;
+    };
+    requests.lock().unwrap().push(target.to_string());
+
+    let (status, body) = routes
+        .iter()
+        .find(|(prefix, _)| target.starts_with(prefix))
+        .map_or(("404 Not Found", ""), |(_, body)| ("200 OK", body));
+    if body == NO_RESPONSE {
+        // Hold the connection open until the client gives up.
+        let _stream = stream;
+        return std::future::pending().await;
+    }
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
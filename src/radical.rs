@@ -0,0 +1,33 @@
+//! The 214 Kangxi radicals (부수).
+
+/// Radicals in Kangxi order, as their unified ideograph forms.
+const KANGXI: &str = "一丨丶丿乙亅二亠人儿入八冂冖冫几凵刀力勹匕匚匸十卜卩厂厶又口囗土士夂夊夕大女子宀寸小尢尸屮山巛工己巾干幺广廴廾弋弓彐彡彳心戈戶手支攴文斗斤方无日曰月木欠止歹殳毋比毛氏气水火爪父爻爿片牙牛犬玄玉瓜瓦甘生用田疋疒癶白皮皿目矛矢石示禸禾穴立竹米糸缶网羊羽老而耒耳聿肉臣自至臼舌舛舟艮色艸虍虫血行衣襾見角言谷豆豕豸貝赤走足身車辛辰辵邑酉釆里金長門阜隶隹雨靑非面革韋韭音頁風飛食首香馬骨高髟鬥鬯鬲鬼魚鳥鹵鹿麥麻黃黍黑黹黽鼎鼓鼠鼻齊齒龍龜龠";
+
+/// Radical with the given Kangxi number, starting from 1.
+pub fn character(number: u8) -> Option<char> {
+    KANGXI.chars().nth(usize::from(number).checked_sub(1)?)
+}
+
+/// Kangxi number of the radical `character`.
+pub fn number(character: char) -> Option<u8> {
+    let index = KANGXI.chars().position(|radical| radical == character)?;
+    u8::try_from(index + 1).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kangxi_numbers() {
+        assert_eq!(KANGXI.chars().count(), 214);
+        assert_eq!(character(0), None);
+        assert_eq!(character(1), Some('一'));
+        assert_eq!(character(39), Some('子'));
+        assert_eq!(character(214), Some('龠'));
+        assert_eq!(character(215), None);
+        assert_eq!(number('子'), Some(39));
+        assert_eq!(number('龠'), Some(214));
+        assert_eq!(number('學'), None);
+    }
+}
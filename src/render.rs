@@ -0,0 +1,802 @@
+//! Presentation of [`HanjaEntry`] for Discord.
+
+use poise::serenity_prelude as serenity;
+use shuttle_runtime::SecretStore;
+
+use crate::backend::RadicalHit;
+use crate::entry::{CharacterGloss, HanjaEntry, RelationKind};
+use crate::idiom::Idiom;
+use crate::radical;
+use crate::store::Word;
+use crate::text::{self, sound};
+
+/// Longest message content Discord accepts.
+const MESSAGE_LIMIT: usize = 2000;
+/// Longest embed field value Discord accepts.
+const FIELD_LIMIT: usize = 1024;
+/// Field characters per embed page, well below Discord's 6000 to stay readable.
+const EMBED_PAGE_LIMIT: usize = 3000;
+/// Most fields Discord accepts in one embed.
+const EMBED_FIELDS: usize = 25;
+/// Characters of the listing on each page of [`radical_index`], leaving room
+/// for the header and page number.
+const RADICAL_PAGE_LIMIT: usize = 1900;
+/// Longest meaning shown per character by [`annotation`] and [`idiom`].
+const GLOSS_LIMIT: usize = 40;
+
+/// Emoji shown before each kind of related character.
+///
+/// Each can be replaced with an `EMOJI_*` secret, e.g. `EMOJI_SYNONYM`.
+#[derive(Debug, Clone)]
+pub struct Emoji {
+    synonym: String,
+    antonym: String,
+    counterpart: String,
+    same: String,
+    abbreviation: String,
+    vulgar: String,
+    simplified: String,
+    other: String,
+}
+
+impl Default for Emoji {
+    fn default() -> Self {
+        Self {
+            synonym: "<:rui:1363124010136764516>".to_string(),
+            antonym: "↔️".to_string(),
+            counterpart: "🔁".to_string(),
+            same: "🟰".to_string(),
+            abbreviation: "✂️".to_string(),
+            vulgar: "✍️".to_string(),
+            simplified: "🇨🇳".to_string(),
+            other: "🔗".to_string(),
+        }
+    }
+}
+
+impl Emoji {
+    pub fn from_secrets(secrets: &SecretStore) -> Self {
+        let mut emoji = Self::default();
+        for (key, value) in [
+            ("EMOJI_SYNONYM", &mut emoji.synonym),
+            ("EMOJI_ANTONYM", &mut emoji.antonym),
+            ("EMOJI_COUNTERPART", &mut emoji.counterpart),
+            ("EMOJI_SAME", &mut emoji.same),
+            ("EMOJI_ABBREVIATION", &mut emoji.abbreviation),
+            ("EMOJI_VULGAR", &mut emoji.vulgar),
+            ("EMOJI_SIMPLIFIED", &mut emoji.simplified),
+            ("EMOJI_OTHER", &mut emoji.other),
+        ] {
+            if let Some(secret) = secrets.get(key) {
+                *value = secret;
+            }
+        }
+        emoji
+    }
+
+    fn get(&self, kind: &RelationKind) -> &str {
+        match kind {
+            RelationKind::Synonym => &self.synonym,
+            RelationKind::Antonym => &self.antonym,
+            RelationKind::Counterpart => &self.counterpart,
+            RelationKind::Same => &self.same,
+            RelationKind::Abbreviation => &self.abbreviation,
+            RelationKind::Vulgar => &self.vulgar,
+            RelationKind::Simplified => &self.simplified,
+            RelationKind::Other(_) => &self.other,
+        }
+    }
+}
+
+/// Label of a kind of relation, as the dictionary titles it.
+fn label(kind: &RelationKind) -> &str {
+    match kind {
+        RelationKind::Synonym => "유의자",
+        RelationKind::Antonym => "반대자",
+        RelationKind::Counterpart => "상대자",
+        RelationKind::Same => "동자",
+        RelationKind::Abbreviation => "약자",
+        RelationKind::Vulgar => "속자",
+        RelationKind::Simplified => "간체자",
+        RelationKind::Other(title) => title,
+    }
+}
+
+/// Plain markdown message, used where embeds are unavailable.
+pub fn message(entry: &HanjaEntry, emoji: &Emoji) -> String {
+    let mut lines = vec![
+        format!("# {}", entry.headword),
+        format!("**{}**", entry.reading),
+    ];
+    lines.extend(meanings(entry));
+    lines.extend(characters(entry));
+    lines.extend(examples(entry));
+    for relation in &entry.relations {
+        lines.push(format!(
+            "{} {} {}",
+            emoji.get(&relation.kind),
+            label(&relation.kind),
+            relation.characters.concat()
+        ));
+    }
+    if let Some(composition) = composition(entry) {
+        lines.push(format!("-# {composition}"));
+    }
+    if let Some(grade) = grade(entry) {
+        lines.push(format!("-# {grade}"));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// [`message`] split into pages that each fit in a Discord message.
+pub fn messages(entry: &HanjaEntry, emoji: &Emoji) -> Vec<String> {
+    let message = message(entry, emoji);
+    if message.chars().count() <= MESSAGE_LIMIT {
+        return vec![message];
+    }
+    // Leave room for the page number.
+    let pages = chunk(message.lines().map(str::to_string), MESSAGE_LIMIT - 16);
+    let total = pages.len();
+    pages
+        .into_iter()
+        .enumerate()
+        .map(|(i, page)| format!("{page}\n-# {}/{total}", i + 1))
+        .collect()
+}
+
+/// Embeds with one field per section, linking to the source dictionary.
+///
+/// Sections too long for one field continue in the next, and fields are
+/// spread over as many embeds as needed.
+pub fn embeds(entry: &HanjaEntry, emoji: &Emoji) -> Vec<serenity::CreateEmbed> {
+    let mut pages = vec![Vec::new()];
+    let mut size = 0;
+    for (name, lines) in sections(entry, emoji) {
+        for (i, value) in chunk(lines, FIELD_LIMIT).into_iter().enumerate() {
+            let name = if i == 0 {
+                name.clone()
+            } else {
+                format!("{name} (계속)")
+            };
+            let len = name.chars().count() + value.chars().count();
+            let page = pages.last_mut().unwrap();
+            if !page.is_empty() && (size + len > EMBED_PAGE_LIMIT || page.len() == EMBED_FIELDS) {
+                pages.push(Vec::new());
+                size = 0;
+            }
+            size += len;
+            pages.last_mut().unwrap().push((name, value));
+        }
+    }
+
+    let total = pages.len();
+    pages
+        .into_iter()
+        .enumerate()
+        .map(|(i, fields)| {
+            let mut embed = serenity::CreateEmbed::new()
+                .title(truncate(&entry.headword, 256))
+                .description(format!("**{}**", entry.reading));
+            for (name, value) in fields {
+                embed = embed.field(name, value, false);
+            }
+            let page = (total > 1).then(|| format!("{}/{total}", i + 1));
+            let footer = match (&entry.source, page) {
+                (Some(source), Some(page)) => Some(format!("{} · {page}", source.name)),
+                (Some(source), None) => Some(source.name.clone()),
+                (None, page) => page,
+            };
+            if let Some(footer) = footer {
+                embed = embed.footer(serenity::CreateEmbedFooter::new(footer));
+            }
+            if let Some(url) = entry.source.as_ref().and_then(|source| source.url.as_ref()) {
+                embed = embed.url(url);
+            }
+            embed
+        })
+        .collect()
+}
+
+/// Non-empty sections of `entry` with their lines.
+fn sections(entry: &HanjaEntry, emoji: &Emoji) -> Vec<(String, Vec<String>)> {
+    let mut sections = vec![
+        ("뜻".to_string(), meanings(entry)),
+        ("글자별 풀이".to_string(), characters(entry)),
+        ("용례".to_string(), examples(entry)),
+    ];
+    for relation in &entry.relations {
+        sections.push((
+            label(&relation.kind).to_string(),
+            vec![format!(
+                "{} {}",
+                emoji.get(&relation.kind),
+                relation.characters.join(", ")
+            )],
+        ));
+    }
+    sections.push((
+        "부수·획수".to_string(),
+        composition(entry).into_iter().collect(),
+    ));
+    sections.push(("급수".to_string(), grade(entry).into_iter().collect()));
+    sections.retain(|(_, lines)| !lines.is_empty());
+    sections
+}
+
+/// Numbered senses under their parts of speech.
+fn meanings(entry: &HanjaEntry) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut number = 0;
+    for group in &entry.meanings {
+        if let Some(part_of_speech) = &group.part_of_speech {
+            lines.push(format!("[{part_of_speech}]"));
+        }
+        for sense in &group.senses {
+            number += 1;
+            lines.push(format!("{number} {sense}"));
+        }
+    }
+    lines
+}
+
+/// One line per character of a compound, e.g. `學 배울 학 — 배우다`.
+fn characters(entry: &HanjaEntry) -> Vec<String> {
+    entry
+        .characters
+        .iter()
+        .map(|gloss| match &gloss.meaning {
+            Some(meaning) => format!("**{}** {} — {meaning}", gloss.character, gloss.reading),
+            None => format!("**{}** {}", gloss.character, gloss.reading),
+        })
+        .collect()
+}
+
+/// Example phrases as quotes, with reading and source.
+fn examples(entry: &HanjaEntry) -> Vec<String> {
+    entry
+        .examples
+        .iter()
+        .map(|example| {
+            let mut line = format!("> {}", example.phrase);
+            if let Some(reading) = &example.reading {
+                line.push_str(&format!("({reading})"));
+            }
+            if let Some(source) = &example.source {
+                line.push_str(&format!(" 《{source}》"));
+            }
+            line
+        })
+        .collect()
+}
+
+/// Radical and stroke count, e.g. `부수 子 · 총 16획`.
+fn composition(entry: &HanjaEntry) -> Option<String> {
+    match (&entry.radical, entry.strokes) {
+        (Some(radical), Some(strokes)) => {
+            Some(format!("부수 {} · 총 {strokes}획", radical.character))
+        }
+        (Some(radical), None) => Some(format!("부수 {}", radical.character)),
+        (None, Some(strokes)) => Some(format!("총 {strokes}획")),
+        (None, None) => None,
+    }
+}
+
+/// `text` with `readings` after its runs of hanja, e.g. `大韓民國(대한민국)`,
+/// followed by one line per character in `glosses`.
+pub fn annotation(text: &str, readings: &[String], glosses: &[CharacterGloss]) -> String {
+    let mut annotated = String::new();
+    let mut readings = readings.iter();
+    for (hanja, run) in text::runs(text, text::is_hanja) {
+        annotated.push_str(run);
+        if !hanja {
+            continue;
+        }
+        if let Some(reading) = readings.next() {
+            annotated.push_str(&format!("({reading})"));
+        }
+    }
+    let mut lines = vec![annotated];
+    lines.extend(glosses.iter().map(gloss_line));
+    truncate(&lines.join("\n"), MESSAGE_LIMIT)
+}
+
+/// `-# **大** 큰 대 — 크다.`, with the meaning cut to [`GLOSS_LIMIT`].
+fn gloss_line(gloss: &CharacterGloss) -> String {
+    match &gloss.meaning {
+        Some(meaning) => format!(
+            "-# **{}** {} — {}",
+            gloss.character,
+            gloss.reading,
+            truncate(meaning, GLOSS_LIMIT)
+        ),
+        None => format!("-# **{}** {}", gloss.character, gloss.reading),
+    }
+}
+
+/// An idiom with the readings of its characters, its meanings, origin and
+/// the `related` idioms known to the table.
+pub fn idiom(idiom: &Idiom, glosses: &[CharacterGloss], related: &[&Idiom]) -> String {
+    let mut lines = vec![
+        format!("# {}", idiom.idiom),
+        format!("**{}**", idiom.reading),
+    ];
+    lines.extend(glosses.iter().map(gloss_line));
+    lines.push(format!("**풀이** {}", idiom.literal));
+    lines.push(format!("**뜻** {}", idiom.meaning));
+    match (&idiom.source, &idiom.origin) {
+        (Some(source), Some(origin)) => lines.push(format!("**유래** 《{source}》 {origin}")),
+        (Some(source), None) => lines.push(format!("**출전** 《{source}》")),
+        (None, Some(origin)) => lines.push(format!("**유래** {origin}")),
+        (None, None) => {}
+    }
+    if !related.is_empty() {
+        let related = related
+            .iter()
+            .map(|idiom| format!("{}({})", idiom.idiom, idiom.reading))
+            .collect::<Vec<_>>();
+        lines.push(format!("**관련 성어** {}", related.join(", ")));
+    }
+    truncate(&lines.join("\n"), MESSAGE_LIMIT)
+}
+
+/// A dictionary word shown in place of an idiom missing from the table, with
+/// the readings of its characters, its meanings and examples.
+pub fn idiom_entry(entry: &HanjaEntry, glosses: &[CharacterGloss]) -> String {
+    let mut lines = vec![
+        format!("# {}", entry.headword),
+        format!("**{}**", entry.reading),
+    ];
+    lines.extend(glosses.iter().map(gloss_line));
+    lines.extend(meanings(entry));
+    lines.extend(examples(entry));
+    truncate(&lines.join("\n"), MESSAGE_LIMIT)
+}
+
+/// Pages of the radical index listing `hits` under radical `number`, one
+/// line per remaining stroke count.
+///
+/// Pages break between stroke counts, and a count with more characters than
+/// fit on a page continues on the next one.
+pub fn radical_index(number: u8, hits: &[RadicalHit]) -> Vec<String> {
+    let radical = radical::character(number).unwrap_or('?');
+    let header = format!("# {radical} (부수 {number})");
+    let mut lines: Vec<String> = Vec::new();
+    let mut remaining = None;
+    for RadicalHit {
+        hit,
+        remaining_strokes,
+    } in hits
+    {
+        let character = match hit.reading.as_deref() {
+            Some(reading) => format!("{}({})", hit.headword, sound(reading)),
+            None => hit.headword.clone(),
+        };
+        match lines.last_mut() {
+            Some(line)
+                if remaining == Some(remaining_strokes)
+                    && line.chars().count() + 1 + character.chars().count()
+                        <= RADICAL_PAGE_LIMIT =>
+            {
+                line.push(' ');
+                line.push_str(&character);
+            }
+            _ => {
+                lines.push(format!("**+{remaining_strokes}** {character}"));
+                remaining = Some(remaining_strokes);
+            }
+        }
+    }
+    if hits.is_empty() {
+        lines.push("-# 등록된 글자가 없습니다.".to_string());
+    }
+    let pages = chunk(lines, RADICAL_PAGE_LIMIT);
+    let count = pages.len();
+    pages
+        .into_iter()
+        .enumerate()
+        .map(|(i, body)| match count {
+            1 => format!("{header}\n{body}"),
+            _ => format!("{header}\n{body}\n-# {}/{count}", i + 1),
+        })
+        .collect()
+}
+
+/// Page `page` of a wordbook, counted from 0, with `per_page` words a page.
+pub fn wordbook(words: &[Word], page: usize, per_page: usize) -> String {
+    let mut lines = vec![format!("# 단어장 ({})", words.len())];
+    lines.extend(
+        words
+            .iter()
+            .skip(page * per_page)
+            .take(per_page)
+            .map(|word| format!("**{}** {}", word.headword, word.reading)),
+    );
+    let pages = words.len().div_ceil(per_page);
+    if words.is_empty() {
+        lines.push(
+            "-# 저장한 글자가 없습니다. 검색 결과의 💾 Save 버튼으로 추가하세요.".to_string(),
+        );
+    } else if pages > 1 {
+        lines.push(format!("-# {}/{pages}", page + 1));
+    }
+    truncate(&lines.join("\n"), MESSAGE_LIMIT)
+}
+
+/// 교육용 기초한자 school and 한자능력검정시험 level, e.g. `중학교 · 8급`.
+fn grade(entry: &HanjaEntry) -> Option<String> {
+    let grade = entry.grade?;
+    match (grade.school, grade.level) {
+        (Some(school), Some(level)) => Some(format!("{school} · {level}")),
+        (Some(school), None) => Some(school.to_string()),
+        (None, Some(level)) => Some(level.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Join `lines` into chunks of at most `max` characters, breaking between lines.
+fn chunk(lines: impl IntoIterator<Item = String>, max: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        let line = truncate(&line, max);
+        if !current.is_empty() && current.chars().count() + 1 + line.chars().count() > max {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&line);
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Cut `text` to at most `max` characters, marking the cut with an ellipsis.
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let mut cut = text.chars().take(max - 1).collect::<String>();
+        cut.push('…');
+        cut
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::SearchHit;
+    use crate::entry::{CharacterGloss, Example, MeaningGroup, Radical, Relation, Source};
+    use crate::grade::{Grade, Level, School};
+
+    fn entry() -> HanjaEntry {
+        let mut entry = HanjaEntry::new("學".to_string(), "배울 학".to_string());
+        entry.meanings = vec![
+            MeaningGroup {
+                part_of_speech: Some("동사".to_string()),
+                senses: vec!["배우다.".to_string(), "가르치다.".to_string()],
+            },
+            MeaningGroup {
+                part_of_speech: None,
+                senses: vec!["학문.".to_string()],
+            },
+        ];
+        entry.examples = vec![Example {
+            phrase: "學而時習之".to_string(),
+            reading: Some("학이시습지".to_string()),
+            source: Some("論語".to_string()),
+        }];
+        entry.relations = vec![
+            Relation {
+                kind: RelationKind::Synonym,
+                characters: vec!["習".to_string(), "修".to_string()],
+            },
+            Relation {
+                kind: RelationKind::Antonym,
+                characters: vec!["敎".to_string()],
+            },
+        ];
+        entry.radical = Some(Radical {
+            character: "子".to_string(),
+            remaining_strokes: Some(13),
+        });
+        entry.strokes = Some(16);
+        entry.grade = Some(Grade {
+            school: Some(School::Middle),
+            level: Some(Level::Grade8),
+        });
+        entry
+    }
+
+    #[test]
+    fn message_layout() {
+        assert_eq!(
+            message(&entry(), &Emoji::default()),
+            "# 學\n**배울 학**\n[동사]\n1 배우다.\n2 가르치다.\n3 학문.\n\
+             > 學而時習之(학이시습지) 《論語》\n\
+             <:rui:1363124010136764516> 유의자 習修\n\
+             ↔️ 반대자 敎\n\
+             -# 부수 子 · 총 16획\n\
+             -# 중학교 · 8급\n"
+        );
+    }
+
+    #[test]
+    fn embed_sections() {
+        let mut entry = entry();
+        entry.source = Some(Source {
+            name: "다음 한자사전".to_string(),
+            url: Some("https://dic.daum.net/word/view.do?wordid=hjdic_0001234".to_string()),
+        });
+        let embeds = embeds(&entry, &Emoji::default());
+        assert_eq!(embeds.len(), 1);
+        let embed = serde_json::to_value(&embeds[0]).unwrap();
+        assert_eq!(embed["title"], "學");
+        assert_eq!(
+            embed["url"],
+            "https://dic.daum.net/word/view.do?wordid=hjdic_0001234"
+        );
+        assert_eq!(embed["description"], "**배울 학**");
+        assert_eq!(embed["footer"]["text"], "다음 한자사전");
+        let names = embed["fields"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|field| field["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["뜻", "용례", "유의자", "반대자", "부수·획수", "급수"]
+        );
+        assert_eq!(
+            embed["fields"][2]["value"],
+            "<:rui:1363124010136764516> 習, 修"
+        );
+        assert_eq!(embed["fields"][3]["value"], "↔️ 敎");
+        assert_eq!(embed["fields"][5]["value"], "중학교 · 8급");
+    }
+
+    #[test]
+    fn embed_skips_empty_sections() {
+        let entry = HanjaEntry::new("人".to_string(), "인".to_string());
+        let embed = serde_json::to_value(&embeds(&entry, &Emoji::default())[0]).unwrap();
+        assert!(embed
+            .get("fields")
+            .is_none_or(|fields| fields.as_array().unwrap().is_empty()));
+        assert!(embed.get("url").is_none());
+    }
+
+    #[test]
+    fn character_breakdown() {
+        let mut entry = HanjaEntry::new("學校".to_string(), "학교".to_string());
+        entry.characters = vec![
+            CharacterGloss {
+                character: "學".to_string(),
+                reading: "배울 학".to_string(),
+                meaning: Some("배우다.".to_string()),
+            },
+            CharacterGloss {
+                character: "校".to_string(),
+                reading: "학교 교".to_string(),
+                meaning: None,
+            },
+        ];
+        assert_eq!(
+            message(&entry, &Emoji::default()),
+            "# 學校\n**학교**\n**學** 배울 학 — 배우다.\n**校** 학교 교\n"
+        );
+        let embed = serde_json::to_value(&embeds(&entry, &Emoji::default())[0]).unwrap();
+        assert_eq!(embed["fields"][0]["name"], "글자별 풀이");
+    }
+
+    #[test]
+    fn annotation_layout() {
+        let gloss = |character: &str, reading: &str, meaning: Option<&str>| CharacterGloss {
+            character: character.to_string(),
+            reading: reading.to_string(),
+            meaning: meaning.map(str::to_string),
+        };
+        let glosses = [
+            gloss("大", "큰 대", Some("크다.")),
+            gloss("韓", "나라 한", None),
+            gloss("民", "백성 민", Some("백성.")),
+        ];
+        assert_eq!(
+            annotation("大韓民國 만세", &["대한민국".to_string()], &glosses),
+            "大韓民國(대한민국) 만세\n\
+             -# **大** 큰 대 — 크다.\n\
+             -# **韓** 나라 한\n\
+             -# **民** 백성 민 — 백성."
+        );
+    }
+
+    #[test]
+    fn idiom_layout() {
+        let idioms = crate::idiom::Idioms::bundled();
+        let idiom = idioms.get("刻舟求劍").unwrap();
+        let related = [idioms.get("守株待兔").unwrap()];
+        let glosses = [CharacterGloss {
+            character: "刻".to_string(),
+            reading: "새길 각".to_string(),
+            meaning: None,
+        }];
+        let content = super::idiom(idiom, &glosses, &related);
+        assert!(content.starts_with(
+            "# 刻舟求劍\n**각주구검**\n-# **刻** 새길 각\n**풀이** 배에 표시를 새겨 칼을 찾는다.\n**뜻** "
+        ));
+        assert!(content.contains("\n**유래** 《呂氏春秋 察今》 초나라 사람이"));
+        assert!(content.ends_with("\n**관련 성어** 守株待兔(수주대토)"));
+
+        let content = super::idiom(idioms.get("大器晩成").unwrap(), &[], &[]);
+        assert!(content.ends_with("\n**출전** 《老子 41章》"));
+    }
+
+    #[test]
+    fn idiom_entry_layout() {
+        let mut entry = HanjaEntry::new("塞翁之馬".to_string(), "새옹지마".to_string());
+        entry.meanings = vec![MeaningGroup {
+            part_of_speech: None,
+            senses: vec!["인생의 길흉화복은 변화가 많아 예측하기 어렵다는 말.".to_string()],
+        }];
+        entry.examples = vec![Example {
+            phrase: "人間萬事塞翁之馬".to_string(),
+            reading: Some("인간만사새옹지마".to_string()),
+            source: None,
+        }];
+        let glosses = [CharacterGloss {
+            character: "塞".to_string(),
+            reading: "변방 새".to_string(),
+            meaning: None,
+        }];
+        assert_eq!(
+            super::idiom_entry(&entry, &glosses),
+            "# 塞翁之馬\n**새옹지마**\n-# **塞** 변방 새\n\
+             1 인생의 길흉화복은 변화가 많아 예측하기 어렵다는 말.\n\
+             > 人間萬事塞翁之馬(인간만사새옹지마)"
+        );
+    }
+
+    #[test]
+    fn wordbook_pages() {
+        let words = ["學", "校", "人"]
+            .map(|headword| Word {
+                headword: headword.to_string(),
+                reading: "?".to_string(),
+            })
+            .to_vec();
+        assert_eq!(
+            wordbook(&words, 0, 2),
+            "# 단어장 (3)\n**學** ?\n**校** ?\n-# 1/2"
+        );
+        assert_eq!(wordbook(&words, 1, 2), "# 단어장 (3)\n**人** ?\n-# 2/2");
+        assert_eq!(
+            wordbook(&words, 0, 5),
+            "# 단어장 (3)\n**學** ?\n**校** ?\n**人** ?"
+        );
+        assert!(wordbook(&[], 0, 5).starts_with("# 단어장 (0)\n-# 저장한 글자가 없습니다."));
+    }
+
+    #[test]
+    fn radical_index_by_remaining_strokes() {
+        let hit = |headword: &str, reading: &str, remaining_strokes| RadicalHit {
+            hit: SearchHit {
+                id: headword.to_string(),
+                headword: headword.to_string(),
+                reading: Some(reading.to_string()),
+                gloss: None,
+                source: "unihan".to_string(),
+            },
+            remaining_strokes,
+        };
+        let hits = [
+            hit("子", "자", 0),
+            hit("孔", "공", 1),
+            hit("字", "자", 3),
+            hit("存", "존", 3),
+        ];
+        assert_eq!(
+            radical_index(39, &hits),
+            ["# 子 (부수 39)\n**+0** 子(자)\n**+1** 孔(공)\n**+3** 字(자) 存(존)"]
+        );
+        assert_eq!(
+            radical_index(214, &[]),
+            ["# 龠 (부수 214)\n-# 등록된 글자가 없습니다."]
+        );
+    }
+
+    #[test]
+    fn large_radicals_are_paginated() {
+        let hits = (0..1200u32)
+            .map(|i| RadicalHit {
+                hit: SearchHit {
+                    id: i.to_string(),
+                    headword: char::from_u32(0x6C34 + i).unwrap().to_string(),
+                    reading: Some("수".to_string()),
+                    gloss: None,
+                    source: "unihan".to_string(),
+                },
+                remaining_strokes: (i / 100) as u8,
+            })
+            .collect::<Vec<_>>();
+        let pages = radical_index(85, &hits);
+        assert!(pages.len() > 3);
+        for (i, page) in pages.iter().enumerate() {
+            assert!(page.chars().count() <= MESSAGE_LIMIT);
+            assert!(page.starts_with("# 水 (부수 85)\n**+"));
+            assert!(page.ends_with(&format!("\n-# {}/{}", i + 1, pages.len())));
+            assert!(!page.contains('…'));
+        }
+        let listed = pages.concat().matches("(수)").count();
+        assert_eq!(listed, hits.len());
+        // Stroke counts start on a new line, and long ones continue under the same count.
+        assert!(pages[0].contains("\n**+1** "));
+        assert!(pages.concat().matches("**+11** ").count() >= 1);
+    }
+
+    fn long_entry() -> HanjaEntry {
+        let mut entry = entry();
+        entry.meanings = vec![MeaningGroup {
+            part_of_speech: None,
+            senses: (0..200)
+                .map(|i| format!("뜻풀이 {i}번째 항목입니다."))
+                .collect(),
+        }];
+        entry
+    }
+
+    #[test]
+    fn long_embeds_are_paginated() {
+        let embeds = embeds(&long_entry(), &Emoji::default())
+            .into_iter()
+            .map(|embed| serde_json::to_value(embed).unwrap())
+            .collect::<Vec<_>>();
+        assert!(embeds.len() > 1);
+        let total = embeds.len();
+        for (i, embed) in embeds.iter().enumerate() {
+            assert_eq!(embed["title"], "學");
+            assert_eq!(embed["footer"]["text"], format!("{}/{total}", i + 1));
+            let fields = embed["fields"].as_array().unwrap();
+            let size = fields
+                .iter()
+                .map(|field| {
+                    let value = field["value"].as_str().unwrap();
+                    assert!(value.chars().count() <= FIELD_LIMIT);
+                    field["name"].as_str().unwrap().chars().count() + value.chars().count()
+                })
+                .sum::<usize>();
+            assert!(size <= EMBED_PAGE_LIMIT);
+        }
+        assert_eq!(embeds[0]["fields"][0]["name"], "뜻");
+        assert_eq!(embeds[0]["fields"][1]["name"], "뜻 (계속)");
+        let all = embeds
+            .iter()
+            .flat_map(|embed| embed["fields"].as_array().unwrap())
+            .map(|field| field["value"].as_str().unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(all.contains("200 뜻풀이 199번째 항목입니다."));
+    }
+
+    #[test]
+    fn long_messages_are_paginated() {
+        let pages = messages(&long_entry(), &Emoji::default());
+        assert!(pages.len() > 1);
+        assert!(pages
+            .iter()
+            .all(|page| page.chars().count() <= MESSAGE_LIMIT));
+        assert!(pages[0].starts_with("# 學\n"));
+        assert!(pages[0].ends_with(&format!("\n-# 1/{}", pages.len())));
+        assert_eq!(
+            messages(&entry(), &Emoji::default()),
+            [message(&entry(), &Emoji::default())]
+        );
+    }
+
+    #[test]
+    fn truncate_marks_cut() {
+        assert_eq!(truncate("學而時習之", 10), "學而時習之");
+        assert_eq!(truncate("學而時習之", 3), "學而…");
+    }
+}
@@ -0,0 +1,176 @@
+//! SM-2 scheduling of wordbook reviews.
+
+use std::fmt;
+
+/// Ease a new card starts with, in thousandths.
+pub const INITIAL_EASE: u32 = 2500;
+/// Lowest ease, keeping hard cards from being shown every day forever.
+const MIN_EASE: u32 = 1300;
+/// Seconds before a forgotten card is due again.
+pub const RELEARN_SECS: u64 = 10 * 60;
+pub const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// How well a card was remembered, from the buttons of `review`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rating {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl Rating {
+    pub const ALL: [Rating; 4] = [Self::Again, Self::Hard, Self::Good, Self::Easy];
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Again => "Again",
+            Self::Hard => "Hard",
+            Self::Good => "Good",
+            Self::Easy => "Easy",
+        })
+    }
+}
+
+/// Scheduling state of a wordbook entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    /// Days until the next review; 0 while the card is being learned.
+    pub interval_days: u32,
+    /// Growth of the interval on a good answer, in thousandths.
+    pub ease: u32,
+    /// Reviews in a row remembered since the card was last forgotten.
+    pub repetitions: u32,
+}
+
+impl Default for Card {
+    fn default() -> Self {
+        Self {
+            interval_days: 0,
+            ease: INITIAL_EASE,
+            repetitions: 0,
+        }
+    }
+}
+
+impl Card {
+    /// The card after a review rated `rating`.
+    pub fn review(self, rating: Rating) -> Self {
+        let ease = match rating {
+            Rating::Again => self.ease.saturating_sub(200),
+            Rating::Hard => self.ease.saturating_sub(150),
+            Rating::Good => self.ease,
+            Rating::Easy => self.ease + 150,
+        }
+        .max(MIN_EASE);
+        let grown = |factor: u32| {
+            let days = (u64::from(self.interval_days) * u64::from(factor)).div_ceil(1000);
+            u32::try_from(days).unwrap_or(u32::MAX)
+        };
+        let interval_days = match (rating, self.repetitions) {
+            (Rating::Again, _) => 0,
+            (Rating::Hard, 0) => 1,
+            (Rating::Hard, _) => grown(1200).max(self.interval_days + 1),
+            (Rating::Good, 0) => 1,
+            (Rating::Good, 1) => 6,
+            (Rating::Good, _) => grown(ease),
+            (Rating::Easy, 0) => 4,
+            (Rating::Easy, _) => grown(ease * 13 / 10).max(7),
+        };
+        Self {
+            interval_days,
+            ease,
+            repetitions: match rating {
+                Rating::Again => 0,
+                _ => self.repetitions + 1,
+            },
+        }
+    }
+
+    /// Seconds from a review until the card is due again.
+    pub fn delay_secs(&self) -> u64 {
+        match self.interval_days {
+            0 => RELEARN_SECS,
+            days => u64::from(days) * DAY_SECS,
+        }
+    }
+}
+
+/// Short delay like `10m` or `6d`.
+pub fn format_delay(secs: u64) -> String {
+    if secs < DAY_SECS {
+        format!("{}m", secs.div_ceil(60))
+    } else {
+        format!("{}d", secs / DAY_SECS)
+    }
+}
+
+/// Days in a row with a review, given the previous streak, the day of the last
+/// review and `today`, counted in days since the Unix epoch.
+pub fn streak(streak: u32, last_day: Option<u64>, today: u64) -> u32 {
+    match last_day {
+        Some(day) if day == today => streak.max(1),
+        Some(day) if day + 1 == today => streak + 1,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intervals(ratings: &[Rating]) -> Vec<u32> {
+        let mut card = Card::default();
+        ratings
+            .iter()
+            .map(|&rating| {
+                card = card.review(rating);
+                card.interval_days
+            })
+            .collect()
+    }
+
+    #[test]
+    fn good_answers_grow_intervals() {
+        use Rating::*;
+        assert_eq!(intervals(&[Good, Good, Good, Good]), [1, 6, 15, 38]);
+        assert_eq!(intervals(&[Good, Good, Hard, Good]), [1, 6, 8, 19]);
+        assert_eq!(intervals(&[Easy, Easy]), [4, 15]);
+    }
+
+    #[test]
+    fn again_starts_over() {
+        let card = Card::default().review(Rating::Good).review(Rating::Good);
+        let forgotten = card.review(Rating::Again);
+        assert_eq!(forgotten.interval_days, 0);
+        assert_eq!(forgotten.repetitions, 0);
+        assert_eq!(forgotten.ease, 2300);
+        assert_eq!(forgotten.delay_secs(), RELEARN_SECS);
+        assert_eq!(forgotten.review(Rating::Good).interval_days, 1);
+    }
+
+    #[test]
+    fn ease_has_a_floor() {
+        let mut card = Card::default();
+        for _ in 0..20 {
+            card = card.review(Rating::Again);
+        }
+        assert_eq!(card.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn delays() {
+        assert_eq!(format_delay(RELEARN_SECS), "10m");
+        assert_eq!(format_delay(6 * DAY_SECS), "6d");
+    }
+
+    #[test]
+    fn streaks() {
+        assert_eq!(streak(0, None, 100), 1);
+        assert_eq!(streak(3, Some(100), 100), 3);
+        assert_eq!(streak(3, Some(99), 100), 4);
+        assert_eq!(streak(3, Some(97), 100), 1);
+    }
+}
@@ -0,0 +1,592 @@
+//! SQLite database keeping guild settings, user data and the lookup cache
+//! across restarts.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use poise::serenity_prelude as serenity;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::review::{self, Card};
+
+/// Schema changes in order; the database's `user_version` counts those applied.
+const MIGRATIONS: &[&str] = &[
+    // 1: passive channels, quiz scores, users, wordbooks and the lookup cache.
+    "CREATE TABLE passive_channels (
+        channel_id INTEGER PRIMARY KEY,
+        guild_id INTEGER NOT NULL
+    );
+    CREATE TABLE users (
+        user_id INTEGER PRIMARY KEY,
+        quiz_points INTEGER NOT NULL DEFAULT 0,
+        quizzes INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE TABLE quiz_scores (
+        id INTEGER PRIMARY KEY,
+        guild_id INTEGER,
+        channel_id INTEGER NOT NULL,
+        user_id INTEGER NOT NULL REFERENCES users (user_id),
+        points INTEGER NOT NULL,
+        played_at INTEGER NOT NULL
+    );
+    CREATE INDEX quiz_scores_by_guild ON quiz_scores (guild_id, user_id);
+    CREATE TABLE wordbook (
+        user_id INTEGER NOT NULL REFERENCES users (user_id),
+        headword TEXT NOT NULL,
+        reading TEXT NOT NULL,
+        added_at INTEGER NOT NULL,
+        PRIMARY KEY (user_id, headword)
+    );
+    CREATE TABLE lookup_cache (
+        kind TEXT NOT NULL,
+        key TEXT NOT NULL,
+        fetched_at INTEGER NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (kind, key)
+    );",
+    // 2: review scheduling of wordbook entries and review streaks.
+    "ALTER TABLE wordbook ADD COLUMN due_at INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE wordbook ADD COLUMN interval_days INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE wordbook ADD COLUMN ease INTEGER NOT NULL DEFAULT 2500;
+    ALTER TABLE wordbook ADD COLUMN repetitions INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE users ADD COLUMN review_streak INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE users ADD COLUMN last_review_day INTEGER;",
+];
+
+/// Handle on the database, shared by commands and the lookup cache.
+pub struct Store {
+    connection: Mutex<Connection>,
+}
+
+/// What a user has done with the bot so far.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Profile {
+    pub quiz_points: u32,
+    /// Quizzes in which the user scored.
+    pub quizzes: u32,
+}
+
+/// Entry saved to a user's wordbook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub headword: String,
+    pub reading: String,
+}
+
+/// Wordbook entry due for review, with its scheduling state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueCard {
+    pub word: Word,
+    pub card: Card,
+}
+
+/// Cached value as stored, with its fetch time in seconds since the Unix epoch.
+pub struct CachedRow {
+    pub key: String,
+    pub fetched_at: u64,
+    pub value: String,
+}
+
+impl Store {
+    /// Open or create the database at `path` and bring its schema up to date.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn in_memory() -> rusqlite::Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(mut connection: Connection) -> rusqlite::Result<Self> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut connection)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn with<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> rusqlite::Result<T> {
+        f(&mut self.connection.lock().unwrap())
+    }
+
+    pub fn passive_channels(&self) -> rusqlite::Result<HashSet<serenity::ChannelId>> {
+        self.with(|connection| {
+            let mut statement = connection.prepare("SELECT channel_id FROM passive_channels")?;
+            let channels = statement
+                .query_map([], |row| Ok(serenity::ChannelId::new(row.get(0)?)))?
+                .collect();
+            channels
+        })
+    }
+
+    pub fn set_passive(
+        &self,
+        guild_id: serenity::GuildId,
+        channel_id: serenity::ChannelId,
+        enabled: bool,
+    ) -> rusqlite::Result<()> {
+        self.with(|connection| {
+            if enabled {
+                connection.execute(
+                    "INSERT OR IGNORE INTO passive_channels (channel_id, guild_id) VALUES (?1, ?2)",
+                    params![channel_id.get(), guild_id.get()],
+                )?;
+            } else {
+                connection.execute(
+                    "DELETE FROM passive_channels WHERE channel_id = ?1",
+                    params![channel_id.get()],
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Add the final `scores` of a quiz to the history and to each user's profile.
+    pub fn record_quiz(
+        &self,
+        guild_id: Option<serenity::GuildId>,
+        channel_id: serenity::ChannelId,
+        scores: &HashMap<serenity::UserId, u32>,
+    ) -> rusqlite::Result<()> {
+        let now = now();
+        self.with(|connection| {
+            let transaction = connection.transaction()?;
+            for (user_id, points) in scores {
+                transaction.execute(
+                    "INSERT INTO users (user_id, quiz_points, quizzes) VALUES (?1, ?2, 1)
+                     ON CONFLICT (user_id) DO UPDATE SET
+                        quiz_points = quiz_points + excluded.quiz_points,
+                        quizzes = quizzes + 1",
+                    params![user_id.get(), points],
+                )?;
+                transaction.execute(
+                    "INSERT INTO quiz_scores (guild_id, channel_id, user_id, points, played_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        guild_id.map(serenity::GuildId::get),
+                        channel_id.get(),
+                        user_id.get(),
+                        points,
+                        now
+                    ],
+                )?;
+            }
+            transaction.commit()
+        })
+    }
+
+    pub fn profile(&self, user_id: serenity::UserId) -> rusqlite::Result<Profile> {
+        self.with(|connection| {
+            let profile = connection
+                .query_row(
+                    "SELECT quiz_points, quizzes FROM users WHERE user_id = ?1",
+                    params![user_id.get()],
+                    |row| {
+                        Ok(Profile {
+                            quiz_points: row.get(0)?,
+                            quizzes: row.get(1)?,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(profile.unwrap_or_default())
+        })
+    }
+
+    /// Users with the most quiz points in `guild_id`, at most `limit` of them.
+    pub fn leaderboard(
+        &self,
+        guild_id: serenity::GuildId,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<(serenity::UserId, u32)>> {
+        self.with(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT user_id, SUM(points) AS total FROM quiz_scores WHERE guild_id = ?1
+                 GROUP BY user_id ORDER BY total DESC, user_id LIMIT ?2",
+            )?;
+            let leaders = statement
+                .query_map(params![guild_id.get(), limit], |row| {
+                    Ok((serenity::UserId::new(row.get(0)?), row.get(1)?))
+                })?
+                .collect();
+            leaders
+        })
+    }
+
+    /// Add `word` to the wordbook of `user_id`, returning whether it was new.
+    pub fn add_word(&self, user_id: serenity::UserId, word: &Word) -> rusqlite::Result<bool> {
+        let now = now();
+        self.with(|connection| {
+            connection.execute(
+                "INSERT OR IGNORE INTO users (user_id) VALUES (?1)",
+                params![user_id.get()],
+            )?;
+            let added = connection.execute(
+                "INSERT OR IGNORE INTO wordbook (user_id, headword, reading, added_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![user_id.get(), word.headword, word.reading, now],
+            )?;
+            Ok(added > 0)
+        })
+    }
+
+    /// Wordbook of `user_id`, most recently saved first.
+    pub fn words(&self, user_id: serenity::UserId) -> rusqlite::Result<Vec<Word>> {
+        self.with(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT headword, reading FROM wordbook WHERE user_id = ?1
+                 ORDER BY added_at DESC, rowid DESC",
+            )?;
+            let words = statement
+                .query_map(params![user_id.get()], |row| {
+                    Ok(Word {
+                        headword: row.get(0)?,
+                        reading: row.get(1)?,
+                    })
+                })?
+                .collect();
+            words
+        })
+    }
+
+    /// Remove `headword` from the wordbook of `user_id`, returning whether it was there.
+    pub fn remove_word(&self, user_id: serenity::UserId, headword: &str) -> rusqlite::Result<bool> {
+        self.with(|connection| {
+            let removed = connection.execute(
+                "DELETE FROM wordbook WHERE user_id = ?1 AND headword = ?2",
+                params![user_id.get(), headword],
+            )?;
+            Ok(removed > 0)
+        })
+    }
+
+    /// Empty the wordbook of `user_id`, returning how many words it held.
+    pub fn clear_words(&self, user_id: serenity::UserId) -> rusqlite::Result<usize> {
+        self.with(|connection| {
+            connection.execute(
+                "DELETE FROM wordbook WHERE user_id = ?1",
+                params![user_id.get()],
+            )
+        })
+    }
+
+    /// Up to `limit` words of `user_id` due at `now`, longest overdue first.
+    pub fn due_cards(
+        &self,
+        user_id: serenity::UserId,
+        now: u64,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<DueCard>> {
+        self.with(|connection| {
+            let mut statement = connection.prepare(
+                "SELECT headword, reading, interval_days, ease, repetitions FROM wordbook
+                 WHERE user_id = ?1 AND due_at <= ?2 ORDER BY due_at, added_at LIMIT ?3",
+            )?;
+            let cards = statement
+                .query_map(params![user_id.get(), now, limit], |row| {
+                    Ok(DueCard {
+                        word: Word {
+                            headword: row.get(0)?,
+                            reading: row.get(1)?,
+                        },
+                        card: Card {
+                            interval_days: row.get(2)?,
+                            ease: row.get(3)?,
+                            repetitions: row.get(4)?,
+                        },
+                    })
+                })?
+                .collect();
+            cards
+        })
+    }
+
+    /// When the next word of `user_id` is due, if there are any.
+    pub fn next_due(&self, user_id: serenity::UserId) -> rusqlite::Result<Option<u64>> {
+        self.with(|connection| {
+            connection.query_row(
+                "SELECT MIN(due_at) FROM wordbook WHERE user_id = ?1",
+                params![user_id.get()],
+                |row| row.get(0),
+            )
+        })
+    }
+
+    /// Save `card` as the state of `headword` after a review at `now`.
+    pub fn schedule(
+        &self,
+        user_id: serenity::UserId,
+        headword: &str,
+        card: Card,
+        now: u64,
+    ) -> rusqlite::Result<()> {
+        self.with(|connection| {
+            connection.execute(
+                "UPDATE wordbook SET due_at = ?3, interval_days = ?4, ease = ?5, repetitions = ?6
+                 WHERE user_id = ?1 AND headword = ?2",
+                params![
+                    user_id.get(),
+                    headword,
+                    now + card.delay_secs(),
+                    card.interval_days,
+                    card.ease,
+                    card.repetitions
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Count a review by `user_id` on `today`, in days since the Unix epoch,
+    /// and return the days in a row they have reviewed.
+    pub fn mark_reviewed(&self, user_id: serenity::UserId, today: u64) -> rusqlite::Result<u32> {
+        self.with(|connection| {
+            let transaction = connection.transaction()?;
+            let (streak, last_day) = transaction
+                .query_row(
+                    "SELECT review_streak, last_review_day FROM users WHERE user_id = ?1",
+                    params![user_id.get()],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?
+                .unwrap_or((0, None));
+            let streak = review::streak(streak, last_day, today);
+            transaction.execute(
+                "INSERT INTO users (user_id, review_streak, last_review_day) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (user_id) DO UPDATE SET
+                    review_streak = excluded.review_streak,
+                    last_review_day = excluded.last_review_day",
+                params![user_id.get(), streak, today],
+            )?;
+            transaction.commit()?;
+            Ok(streak)
+        })
+    }
+
+    /// Cached values of `kind` fetched at or after `since`, dropping older ones.
+    pub fn cached(&self, kind: &str, since: u64) -> rusqlite::Result<Vec<CachedRow>> {
+        self.with(|connection| {
+            connection.execute(
+                "DELETE FROM lookup_cache WHERE kind = ?1 AND fetched_at < ?2",
+                params![kind, since],
+            )?;
+            let mut statement = connection.prepare(
+                "SELECT key, fetched_at, value FROM lookup_cache WHERE kind = ?1
+                 ORDER BY fetched_at",
+            )?;
+            let rows = statement
+                .query_map(params![kind], |row| {
+                    Ok(CachedRow {
+                        key: row.get(0)?,
+                        fetched_at: row.get(1)?,
+                        value: row.get(2)?,
+                    })
+                })?
+                .collect();
+            rows
+        })
+    }
+
+    pub fn cache(&self, kind: &str, row: &CachedRow) -> rusqlite::Result<()> {
+        self.with(|connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO lookup_cache (kind, key, fetched_at, value)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![kind, row.key, row.fetched_at, row.value],
+            )?;
+            Ok(())
+        })
+    }
+}
+
+/// Apply the [`MIGRATIONS`] the database has not seen yet.
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let applied: usize = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", version + 1)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_are_applied_once() {
+        let path = std::env::temp_dir().join(format!("gajibot-store-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let store = Store::open(&path).unwrap();
+        let channel = serenity::ChannelId::new(2);
+        store
+            .set_passive(serenity::GuildId::new(1), channel, true)
+            .unwrap();
+        drop(store);
+
+        let store = Store::open(&path).unwrap();
+        let version: usize = store
+            .with(|connection| {
+                connection.pragma_query_value(None, "user_version", |row| row.get(0))
+            })
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+        assert_eq!(store.passive_channels().unwrap(), HashSet::from([channel]));
+        drop(store);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn passive_channels() {
+        let store = Store::in_memory().unwrap();
+        let guild = serenity::GuildId::new(1);
+        let (first, second) = (serenity::ChannelId::new(2), serenity::ChannelId::new(3));
+        store.set_passive(guild, first, true).unwrap();
+        store.set_passive(guild, first, true).unwrap();
+        store.set_passive(guild, second, true).unwrap();
+        store.set_passive(guild, second, false).unwrap();
+        assert_eq!(store.passive_channels().unwrap(), HashSet::from([first]));
+    }
+
+    #[test]
+    fn quiz_scores_add_up() {
+        let store = Store::in_memory().unwrap();
+        let guild = serenity::GuildId::new(1);
+        let channel = serenity::ChannelId::new(2);
+        let (alice, bob) = (serenity::UserId::new(10), serenity::UserId::new(11));
+        store
+            .record_quiz(Some(guild), channel, &HashMap::from([(alice, 3), (bob, 5)]))
+            .unwrap();
+        store
+            .record_quiz(Some(guild), channel, &HashMap::from([(alice, 4)]))
+            .unwrap();
+        store
+            .record_quiz(None, channel, &HashMap::from([(bob, 9)]))
+            .unwrap();
+
+        assert_eq!(
+            store.profile(alice).unwrap(),
+            Profile {
+                quiz_points: 7,
+                quizzes: 2
+            }
+        );
+        assert_eq!(store.profile(bob).unwrap().quiz_points, 14);
+        assert_eq!(
+            store.profile(serenity::UserId::new(12)).unwrap(),
+            Profile::default()
+        );
+        assert_eq!(
+            store.leaderboard(guild, 10).unwrap(),
+            [(alice, 7), (bob, 5)]
+        );
+        assert_eq!(store.leaderboard(guild, 1).unwrap(), [(alice, 7)]);
+    }
+
+    #[test]
+    fn wordbooks_are_per_user() {
+        let store = Store::in_memory().unwrap();
+        let (alice, bob) = (serenity::UserId::new(10), serenity::UserId::new(11));
+        let word = |headword: &str, reading: &str| Word {
+            headword: headword.to_string(),
+            reading: reading.to_string(),
+        };
+        assert!(store.add_word(alice, &word("學", "배울 학")).unwrap());
+        assert!(store.add_word(alice, &word("校", "학교 교")).unwrap());
+        assert!(!store.add_word(alice, &word("學", "배울 학")).unwrap());
+        assert!(store.add_word(bob, &word("學", "배울 학")).unwrap());
+
+        assert_eq!(
+            store.words(alice).unwrap(),
+            [word("校", "학교 교"), word("學", "배울 학")]
+        );
+        assert!(store.remove_word(alice, "校").unwrap());
+        assert!(!store.remove_word(alice, "校").unwrap());
+        assert_eq!(store.words(alice).unwrap(), [word("學", "배울 학")]);
+        assert_eq!(store.clear_words(alice).unwrap(), 1);
+        assert!(store.words(alice).unwrap().is_empty());
+        assert_eq!(store.words(bob).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reviews_are_scheduled() {
+        let store = Store::in_memory().unwrap();
+        let user = serenity::UserId::new(10);
+        let word = |headword: &str| Word {
+            headword: headword.to_string(),
+            reading: "?".to_string(),
+        };
+        assert_eq!(store.next_due(user).unwrap(), None);
+        store.add_word(user, &word("學")).unwrap();
+        store.add_word(user, &word("校")).unwrap();
+
+        let due = store.due_cards(user, 1000, 10).unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].card, Card::default());
+        let card = due[0].card.review(review::Rating::Good);
+        store
+            .schedule(user, &due[0].word.headword, card, 1000)
+            .unwrap();
+
+        let due = store.due_cards(user, 1000, 10).unwrap();
+        assert_eq!(due.len(), 1);
+        store
+            .schedule(user, &due[0].word.headword, Card::default(), 1000)
+            .unwrap();
+        assert!(store.due_cards(user, 1000, 10).unwrap().is_empty());
+        assert_eq!(
+            store.next_due(user).unwrap(),
+            Some(1000 + review::RELEARN_SECS)
+        );
+        let due = store.due_cards(user, 1000 + review::DAY_SECS, 10).unwrap();
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[1].card, card);
+    }
+
+    #[test]
+    fn review_streaks() {
+        let store = Store::in_memory().unwrap();
+        let user = serenity::UserId::new(10);
+        assert_eq!(store.mark_reviewed(user, 100).unwrap(), 1);
+        assert_eq!(store.mark_reviewed(user, 100).unwrap(), 1);
+        assert_eq!(store.mark_reviewed(user, 101).unwrap(), 2);
+        assert_eq!(store.mark_reviewed(user, 103).unwrap(), 1);
+    }
+
+    #[test]
+    fn cache_drops_expired_rows() {
+        let store = Store::in_memory().unwrap();
+        let row = |key: &str, fetched_at| CachedRow {
+            key: key.to_string(),
+            fetched_at,
+            value: "[]".to_string(),
+        };
+        store.cache("search", &row("學", 100)).unwrap();
+        store.cache("search", &row("人", 200)).unwrap();
+        store.cache("search", &row("學", 300)).unwrap();
+        store.cache("entry", &row("daum:1", 50)).unwrap();
+
+        let keys = |rows: Vec<CachedRow>| rows.into_iter().map(|row| row.key).collect::<Vec<_>>();
+        assert_eq!(keys(store.cached("search", 150).unwrap()), ["人", "學"]);
+        assert_eq!(keys(store.cached("search", 250).unwrap()), ["學"]);
+        assert_eq!(keys(store.cached("entry", 0).unwrap()), ["daum:1"]);
+    }
+}
@@ -0,0 +1,169 @@
+//! Helpers for text mixing hangul and hanja.
+
+/// Whether `c` is a CJK unified or compatibility ideograph.
+pub fn is_hanja(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2EBEF}'
+            | '\u{30000}'..='\u{3134F}'
+    )
+}
+
+/// Split `text` into alternating runs of characters matching `class` and
+/// other text, tagging each run with whether it matches.
+pub fn runs(text: &str, class: impl Fn(char) -> bool) -> Vec<(bool, &str)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut current = None;
+    for (i, c) in text.char_indices() {
+        let matches = class(c);
+        if current.is_some_and(|current| current != matches) {
+            runs.push((!matches, &text[start..i]));
+            start = i;
+        }
+        current = Some(matches);
+    }
+    if let Some(matches) = current {
+        runs.push((matches, &text[start..]));
+    }
+    runs
+}
+
+/// Whether `c` is a precomposed hangul syllable.
+pub fn is_hangul(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7A3}')
+}
+
+const SYLLABLE_BASE: u32 = 0xAC00;
+const MEDIALS: u32 = 21;
+const FINALS: u32 = 28;
+/// Initial consonant indices of ㄴ, ㄹ and ㅇ.
+const NIEUN: u32 = 2;
+const RIEUL: u32 = 5;
+const IEUNG: u32 = 11;
+
+/// Apply the initial sound law (두음법칙) to the first syllable of a word:
+/// `리` → `이`, `녀자` → `여자`, `로인` → `노인`.
+pub fn initial_sound(word: &str) -> String {
+    let mut chars = word.chars();
+    let Some(first) = chars.next() else {
+        return String::new();
+    };
+    let mut converted = String::from(initial_sound_syllable(first));
+    converted.extend(chars);
+    converted
+}
+
+fn initial_sound_syllable(syllable: char) -> char {
+    let Some(index) = u32::from(syllable)
+        .checked_sub(SYLLABLE_BASE)
+        .filter(|&index| index < 19 * MEDIALS * FINALS)
+    else {
+        return syllable;
+    };
+    let initial = index / (MEDIALS * FINALS);
+    let medial = index / FINALS % MEDIALS;
+    // ㅑ ㅕ ㅖ ㅛ ㅠ ㅣ
+    let palatal = matches!(medial, 2 | 6 | 7 | 12 | 17 | 20);
+    let initial = match initial {
+        NIEUN | RIEUL if palatal => IEUNG,
+        RIEUL => NIEUN,
+        initial => initial,
+    };
+    char::from_u32(SYLLABLE_BASE + (initial * MEDIALS + medial) * FINALS + index % FINALS)
+        .unwrap_or(syllable)
+}
+
+/// Whether `text` is one precomposed hangul syllable, e.g. `학`.
+pub fn is_syllable(text: &str) -> bool {
+    let mut chars = text.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if is_hangul(c))
+}
+
+/// The sound (음) of a reading: `배울 학` → `학`, `락, 악, 요` → `락`.
+pub fn sound(reading: &str) -> &str {
+    let first = reading.split(',').next().unwrap_or(reading);
+    first.split_whitespace().last().unwrap_or(first)
+}
+
+/// Plain hangul reading of `headword`: the [`sound`] of a character, or the
+/// first reading of a word without spaces.
+pub fn spoken(headword: &str, reading: &str) -> String {
+    if headword.chars().count() == 1 {
+        sound(reading).to_string()
+    } else {
+        let first = reading.split(',').next().unwrap_or_default();
+        first.split_whitespace().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hanja_ranges() {
+        assert!(is_hanja('學'));
+        assert!(is_hanja('樂'));
+        assert!(is_hanja('\u{F9BF}'));
+        assert!(!is_hanja('학'));
+        assert!(!is_hanja('a'));
+        assert!(!is_hanja('。'));
+    }
+
+    #[test]
+    fn hanja_runs() {
+        assert_eq!(
+            runs("大韓民國 만세, 萬歲!", is_hanja),
+            [
+                (true, "大韓民國"),
+                (false, " 만세, "),
+                (true, "萬歲"),
+                (false, "!"),
+            ]
+        );
+        assert_eq!(runs("學", is_hanja), [(true, "學")]);
+        assert_eq!(runs("국민은 ", is_hangul), [(true, "국민은"), (false, " ")]);
+        assert!(runs("", is_hanja).is_empty());
+    }
+
+    #[test]
+    fn initial_sound_law() {
+        assert_eq!(initial_sound("리"), "이");
+        assert_eq!(initial_sound("녀자"), "여자");
+        assert_eq!(initial_sound("남녀"), "남녀");
+        assert_eq!(initial_sound("로인"), "노인");
+        assert_eq!(initial_sound("력사"), "역사");
+        assert_eq!(initial_sound("락원"), "낙원");
+        assert_eq!(initial_sound("뉴"), "유");
+        assert_eq!(initial_sound("학교"), "학교");
+        assert_eq!(initial_sound(""), "");
+    }
+
+    #[test]
+    fn syllables() {
+        assert!(is_syllable("학"));
+        assert!(!is_syllable("학교"));
+        assert!(!is_syllable("ㅎ"));
+        assert!(!is_syllable("學"));
+        assert!(!is_syllable(""));
+    }
+
+    #[test]
+    fn sound_of_readings() {
+        assert_eq!(sound("배울 학"), "학");
+        assert_eq!(sound("락, 악, 요"), "락");
+        assert_eq!(sound("학"), "학");
+        assert_eq!(sound(""), "");
+    }
+
+    #[test]
+    fn spoken_readings() {
+        assert_eq!(spoken("學", "배울 학"), "학");
+        assert_eq!(spoken("大韓民國", "대한 민국"), "대한민국");
+        assert_eq!(spoken("樂園", "낙원, 락원"), "낙원");
+    }
+}
@@ -0,0 +1,196 @@
+//! In-memory index over Unihan database fields.
+//!
+//! Only `kHangul`, `kDefinition`, `kRSUnicode`, `kTotalStrokes` and
+//! `kFrequency` are kept; other fields and malformed lines are skipped.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+const BUNDLED: &str = include_str!("../data/unihan.txt");
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Record {
+    /// Hangul readings, most common first.
+    pub readings: Vec<String>,
+    pub definition: Option<String>,
+    /// Kangxi radical number and remaining stroke count.
+    pub radical: Option<(u8, u8)>,
+    pub strokes: Option<u8>,
+    /// Usage frequency in Chinese texts, 1 (most) to 5 (least frequent).
+    pub frequency: Option<u8>,
+}
+
+#[derive(Debug, Default)]
+pub struct UnihanIndex {
+    records: HashMap<char, Record>,
+}
+
+impl UnihanIndex {
+    /// The subset shipped in `data/unihan.txt`.
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut records = HashMap::<char, Record>::new();
+        for line in text.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(3, '\t');
+            let (Some(code), Some(field), Some(value)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let Some(character) = code
+                .strip_prefix("U+")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .and_then(char::from_u32)
+            else {
+                continue;
+            };
+            let record = records.entry(character).or_default();
+            match field {
+                "kHangul" => {
+                    record.readings = value
+                        .split_whitespace()
+                        .map(|reading| reading.split(':').next().unwrap_or(reading).to_string())
+                        .collect();
+                }
+                "kDefinition" => record.definition = Some(value.to_string()),
+                "kRSUnicode" => record.radical = value.split_whitespace().next().and_then(parse_rs),
+                "kTotalStrokes" => {
+                    record.strokes = value.split_whitespace().next().and_then(|s| s.parse().ok());
+                }
+                "kFrequency" => record.frequency = value.trim().parse().ok(),
+                _ => {}
+            }
+        }
+        Self { records }
+    }
+
+    pub fn get(&self, character: char) -> Option<&Record> {
+        self.records.get(&character)
+    }
+
+    /// All characters, most frequent and then simplest first.
+    pub fn characters(&self) -> Vec<(char, &Record)> {
+        let mut characters = self
+            .records
+            .iter()
+            .map(|(&character, record)| (character, record))
+            .collect::<Vec<_>>();
+        characters.sort_by_key(|&(character, record)| {
+            (
+                record.frequency.unwrap_or(u8::MAX),
+                record.strokes.unwrap_or(u8::MAX),
+                character,
+            )
+        });
+        characters
+    }
+
+    /// Characters under Kangxi radical `number`, by remaining strokes and
+    /// then in the order of [`Self::characters`].
+    pub fn with_radical(&self, number: u8) -> Vec<(char, &Record)> {
+        let mut found = self
+            .characters()
+            .into_iter()
+            .filter(|(_, record)| record.radical.is_some_and(|(radical, _)| radical == number))
+            .collect::<Vec<_>>();
+        found.sort_by_key(|(_, record)| record.radical.map(|(_, remaining)| remaining));
+        found
+    }
+
+    /// Characters read as `reading`, in the order of [`Self::characters`].
+    pub fn with_reading(&self, reading: &str) -> Vec<(char, &Record)> {
+        self.characters()
+            .into_iter()
+            .filter(|(_, record)| record.readings.iter().any(|r| r == reading))
+            .collect()
+    }
+}
+
+/// Parse a `kRSUnicode` value like `39.13` or the simplified form `120'.3`.
+fn parse_rs(value: &str) -> Option<(u8, u8)> {
+    let (radical, remaining) = value.split_once('.')?;
+    let radical = radical.trim_end_matches('\'').parse().ok()?;
+    Some((radical, remaining.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_fields() {
+        let index = UnihanIndex::parse(
+            "# comment\n\
+             U+5B78\tkDefinition\tlearning, knowledge; school\n\
+             U+5B78\tkHangul\t학:0E\n\
+             U+5B78\tkRSUnicode\t39.13\n\
+             U+5B78\tkTotalStrokes\t16\n\
+             U+5B78\tkMandarin\txué\n\
+             U+5B78\tkFrequency\t2\n\
+             U+7E9F\tkRSUnicode\t120'.3\n\
+             U+6A02\tkHangul\t락:0E 악:0E 요:0E\n\
+             not a record\n",
+        );
+        assert_eq!(
+            index.get('學'),
+            Some(&Record {
+                readings: vec!["학".to_string()],
+                definition: Some("learning, knowledge; school".to_string()),
+                radical: Some((39, 13)),
+                strokes: Some(16),
+                frequency: Some(2),
+            })
+        );
+        assert_eq!(index.get('纟').unwrap().radical, Some((120, 3)));
+        assert_eq!(index.get('樂').unwrap().readings, ["락", "악", "요"]);
+        assert_eq!(index.get('人'), None);
+    }
+
+    #[test]
+    fn bundled_data() {
+        let index = UnihanIndex::bundled();
+        let record = index.get('學').unwrap();
+        assert_eq!(record.readings, ["학"]);
+        assert_eq!(record.radical, Some((39, 13)));
+        let under_child = index
+            .with_radical(39)
+            .into_iter()
+            .map(|(character, _)| character)
+            .collect::<String>();
+        assert_eq!(under_child, "子字學");
+    }
+
+    #[test]
+    fn characters_by_reading() {
+        let index = UnihanIndex::parse(
+            "U+6C34\tkHangul\t수:0E\n\
+             U+6C34\tkTotalStrokes\t4\n\
+             U+4FEE\tkHangul\t수:0E\n\
+             U+4FEE\tkTotalStrokes\t10\n\
+             U+4FEE\tkFrequency\t1\n\
+             U+624B\tkHangul\t수:0E\n\
+             U+624B\tkTotalStrokes\t4\n\
+             U+6A02\tkHangul\t락:0E 악:0E 요:0E\n",
+        );
+        let found = |reading| {
+            index
+                .with_reading(reading)
+                .into_iter()
+                .map(|(character, _)| character)
+                .collect::<String>()
+        };
+        assert_eq!(found("수"), "修手水");
+        assert_eq!(found("악"), "樂");
+        assert_eq!(found("학"), "");
+    }
+}